@@ -1,14 +1,17 @@
 use std::f64::consts::PI;
 
+#[cfg(feature = "gui")]
 use eframe::egui;
 use nalgebra::Vector2;
 use super::geometry::{vec2, ToPoints};
 use super::geometry;
+use crate::error::OpenChuteError;
 
 use serde::{Serialize, Deserialize};
 
 // Can be used to set options
 pub trait ConfigurableGeometry: geometry::ToPoints {
+    #[cfg(feature = "gui")]
     fn ui(&mut self, ui: &mut eframe::egui::Ui, frame: &mut eframe::Frame, use_imperial: bool, evaluator_context: &evalexpr::HashMapContext);
     fn update_from_context(&mut self, evaluator_context: &evalexpr::HashMapContext);
 }
@@ -35,6 +38,7 @@ impl ToPoints for ConfigurableLine {
 }
 
 impl ConfigurableGeometry for ConfigurableLine {
+    #[cfg(feature = "gui")]
     fn ui(&mut self, ui: &mut eframe::egui::Ui, frame: &mut eframe::Frame, use_imperial: bool, evaluator_context: &evalexpr::HashMapContext) {
         ui.label("Line geometry:");
 
@@ -83,6 +87,7 @@ impl ToPoints for ConfigurableEllipse {
 }
 
 impl ConfigurableGeometry for ConfigurableEllipse {
+    #[cfg(feature = "gui")]
     fn ui(&mut self, ui: &mut eframe::egui::Ui, frame: &mut eframe::Frame, use_imperial: bool, evaluator_context: &evalexpr::HashMapContext) {
         ui.label("Ellipse geometry:");
 
@@ -155,6 +160,7 @@ impl ToPoints for ConfigurablePointList {
 }
 
 impl ConfigurableGeometry for ConfigurablePointList {
+    #[cfg(feature = "gui")]
     fn ui(&mut self, ui: &mut eframe::egui::Ui, frame: &mut eframe::Frame, use_imperial: bool, evaluator_context: &evalexpr::HashMapContext) {
         ui.label("Point list");
 
@@ -209,4 +215,192 @@ impl ConfigurablePointList {
         Self { geo: geometry::Points::new(), point_list: "0,0\n1,0".into(), scale_x: "1".into(), scale_y: "1".into(), offset: ["0.0".into(), "0.0".into()],
                 offset_f: (0.0, 0.0), scale_x_f: 1.0, scale_y_f: 1.0, }   
     }
-}
\ No newline at end of file
+}
+// Half cross-section imported from the first polyline or spline in a DXF file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConfigurableDxfProfile {
+    geo: geometry::Points,
+    file_path: String,
+    profile: Vec<Vector2<f64>>, // Imported points in m, before scale and offset
+    scale_x: String,
+    scale_y: String,
+    offset: [String; 2],
+    #[serde(skip)]
+    #[cfg_attr(not(feature = "gui"), allow(dead_code))] // Only shown in the editor
+    import_error: Option<String>,
+}
+
+// Number of points a spline without fit points is sampled with
+const DXF_SPLINE_RESOLUTION: u32 = 100;
+
+// Compares the imported profile, not where it was imported from
+impl PartialEq for ConfigurableDxfProfile {
+    fn eq(&self, other: &Self) -> bool {
+        self.geo == other.geo && self.profile == other.profile
+            && self.scale_x == other.scale_x && self.scale_y == other.scale_y && self.offset == other.offset
+    }
+}
+
+impl ToPoints for ConfigurableDxfProfile {
+    fn to_points(&self, _resolution: u32) -> geometry::Points {
+        self.geo.clone()
+    }
+}
+
+impl ConfigurableGeometry for ConfigurableDxfProfile {
+    #[cfg(feature = "gui")]
+    fn ui(&mut self, ui: &mut eframe::egui::Ui, _frame: &mut eframe::Frame, _use_imperial: bool, evaluator_context: &evalexpr::HashMapContext) {
+        ui.label("DXF profile");
+
+        if crate::ui::file_import(ui, &mut self.file_path, &self.import_error, "Choose DXF file", "Uses the first polyline or spline in the drawing", &["dxf"]) {
+            self.import_error = self.import().err().map(|e| e.to_string());
+        }
+        if self.import_error.is_none() {
+            ui.label(format!("{} points imported", self.profile.len()));
+        }
+
+        ui.label("Scale x-y");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.scale_x).clip_text(false).desired_width(200.0));
+            ui.add(egui::TextEdit::singleline(&mut self.scale_y).clip_text(false).desired_width(200.0));
+        });
+
+        ui.label("Offset x-y:");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.offset[0]).clip_text(false).desired_width(200.0));
+            ui.add(egui::TextEdit::singleline(&mut self.offset[1]).clip_text(false).desired_width(200.0));
+        });
+
+        self.update_from_context(evaluator_context);
+    }
+
+    fn update_from_context(&mut self, evaluator_context: &evalexpr::HashMapContext) {
+        let eval = |expr: &str| evalexpr::eval_number_with_context(expr, evaluator_context).unwrap_or(0.0);
+        let scale = vec2(eval(&self.scale_x), eval(&self.scale_y));
+        let offset = vec2(eval(&self.offset[0]), eval(&self.offset[1]));
+
+        self.geo.points = self.profile.iter().map(|pt| pt.component_mul(&scale) + offset).collect();
+    }
+}
+
+impl ConfigurableDxfProfile {
+    pub fn new() -> Self {
+        Self { geo: geometry::Points::new(), file_path: "".into(), profile: vec![], scale_x: "1".into(), scale_y: "1".into(), offset: ["0.0".into(), "0.0".into()], import_error: None }
+    }
+}
+
+impl Default for ConfigurableDxfProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigurableDxfProfile {
+
+    // Reads the profile from `file_path`. Points are only replaced if the import succeeds
+    pub fn import(&mut self) -> Result<(), OpenChuteError> {
+        let drawing = dxf::Drawing::load_file(&self.file_path)?;
+        self.profile = profile_from_drawing(&drawing)?;
+        Ok(())
+    }
+}
+
+// Points of the first polyline or spline in the drawing, converted to m. Unitless drawings are assumed to be in mm
+fn profile_from_drawing(drawing: &dxf::Drawing) -> Result<Vec<Vector2<f64>>, OpenChuteError> {
+    use dxf::entities::EntityType;
+    use dxf::enums::Units;
+
+    let unit = match drawing.header.default_drawing_units {
+        Units::Meters => 1.0,
+        Units::Centimeters => 0.01,
+        Units::Inches => 0.0254,
+        Units::Feet => 0.3048,
+        _ => 0.001,
+    };
+    let to_vec = |pt: &dxf::Point| vec2(pt.x, pt.y);
+
+    for entity in drawing.entities() {
+        let points: Vec<Vector2<f64>> = match &entity.specific {
+            EntityType::LwPolyline(polyline) => polyline.vertices.iter().map(|vertex| vec2(vertex.x, vertex.y)).collect(),
+            EntityType::Polyline(polyline) => polyline.vertices().map(|vertex| to_vec(&vertex.location)).collect(),
+            EntityType::Spline(spline) if !spline.fit_points.is_empty() => spline.fit_points.iter().map(to_vec).collect(),
+            EntityType::Spline(spline) => {
+                let control: Vec<Vector2<f64>> = spline.control_points.iter().map(to_vec).collect();
+                evaluate_spline(&control, &spline.knot_values, spline.degree_of_curve.max(1) as usize, DXF_SPLINE_RESOLUTION)
+            },
+            _ => continue,
+        };
+
+        if points.len() >= 2 {
+            return Ok(points.into_iter().map(|pt| pt * unit).collect());
+        }
+    }
+
+    Err(OpenChuteError::InvalidImport("DXF file contains no polyline or spline".into()))
+}
+
+// Samples a B-spline with de Boor's algorithm. Falls back to the control points if the knots don't match
+fn evaluate_spline(control: &[Vector2<f64>], knots: &[f64], degree: usize, resolution: u32) -> Vec<Vector2<f64>> {
+    let n = control.len();
+    if n <= degree || knots.len() != n + degree + 1 {
+        return control.to_vec();
+    }
+
+    let (start, end) = (knots[degree], knots[n]);
+    let mut result = vec![];
+
+    for i in 0..=resolution {
+        let u = start + (end - start) * i as f64 / resolution as f64;
+        // Knot span containing u
+        let k = (degree..n).rev().find(|&k| knots[k] <= u && knots[k] < knots[k + 1]).unwrap_or(degree);
+
+        let mut d: Vec<Vector2<f64>> = control[k - degree..=k].to_vec();
+        for r in 1..=degree {
+            for j in (r..=degree).rev() {
+                let denominator = knots[j + 1 + k - r] - knots[j + k - degree];
+                let alpha = if denominator.abs() > 1e-12 { (u - knots[j + k - degree]) / denominator } else { 0.0 };
+                d[j] = d[j - 1] * (1.0 - alpha) + d[j] * alpha;
+            }
+        }
+        result.push(d[degree]);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_spline, profile_from_drawing};
+    use crate::geometry::vec2;
+    use dxf::entities::{Entity, EntityType, LwPolyline};
+    use dxf::LwPolylineVertex;
+
+    #[test]
+    fn test_spline() {
+        // Quadratic Bezier curve
+        let control = vec![vec2(0.0, 0.0), vec2(1.0, 2.0), vec2(2.0, 0.0)];
+        let points = evaluate_spline(&control, &[0.0, 0.0, 0.0, 1.0, 1.0, 1.0], 2, 10);
+
+        assert_eq!(points.len(), 11);
+        assert!((points[0] - control[0]).norm() < 1e-9);
+        assert!((points[5] - vec2(1.0, 1.0)).norm() < 1e-9);
+        assert!((points[10] - control[2]).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_dxf_profile() {
+        let mut drawing = dxf::Drawing::new();
+        assert!(profile_from_drawing(&drawing).is_err());
+
+        let mut polyline = LwPolyline::default();
+        for (x, y) in [(0.0, 500.0), (400.0, 300.0), (500.0, 0.0)] {
+            polyline.vertices.push(LwPolylineVertex { x, y, ..Default::default() });
+        }
+        drawing.add_entity(Entity::new(EntityType::LwPolyline(polyline)));
+        drawing.header.default_drawing_units = dxf::enums::Units::Millimeters;
+
+        let profile = profile_from_drawing(&drawing).unwrap();
+        assert_eq!(profile.len(), 3);
+        assert!((profile[1] - vec2(0.4, 0.3)).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+
+// Errors that can happen when loading, saving or exporting a design
+#[derive(Debug)]
+pub enum OpenChuteError {
+    Io(std::io::Error), // Reading or writing a file failed
+    Parse(serde_json::Error), // Design file is not valid
+    InvalidFormat(String), // Design file is valid JSON, but not a design
+    UnsupportedVersion(u64), // Design file format version is newer than this build supports
+    Dxf(dxf::DxfError), // Writing the DXF drawing failed
+    Pdf(printpdf::Error), // Writing the PDF document failed
+    InvalidPattern(String), // Pattern pieces can't be made from the design, e.g. seam allowance folds over itself
+    InvalidImport(String), // Imported file was read, but has no usable geometry
+}
+
+impl fmt::Display for OpenChuteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenChuteError::Io(e) => write!(f, "File error: {}", e),
+            OpenChuteError::Parse(e) => write!(f, "Invalid design file: {}", e),
+            OpenChuteError::InvalidFormat(msg) => write!(f, "Invalid design file: {}", msg),
+            OpenChuteError::UnsupportedVersion(version) => write!(f, "Design file has format version {}, but this version of OpenChute only supports up to version {}. Please update OpenChute", version, crate::file_format::FORMAT_VERSION),
+            OpenChuteError::Dxf(e) => write!(f, "DXF export failed: {}", e),
+            OpenChuteError::Pdf(e) => write!(f, "PDF export failed: {}", e),
+            OpenChuteError::InvalidPattern(msg) => write!(f, "Invalid pattern: {}", msg),
+            OpenChuteError::InvalidImport(msg) => write!(f, "Import failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for OpenChuteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OpenChuteError::Io(e) => Some(e),
+            OpenChuteError::Parse(e) => Some(e),
+            OpenChuteError::Dxf(e) => Some(e),
+            OpenChuteError::Pdf(e) => Some(e),
+            OpenChuteError::InvalidFormat(_) | OpenChuteError::UnsupportedVersion(_) | OpenChuteError::InvalidPattern(_) | OpenChuteError::InvalidImport(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for OpenChuteError {
+    fn from(e: std::io::Error) -> Self {
+        OpenChuteError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for OpenChuteError {
+    fn from(e: serde_json::Error) -> Self {
+        OpenChuteError::Parse(e)
+    }
+}
+
+impl From<dxf::DxfError> for OpenChuteError {
+    fn from(e: dxf::DxfError) -> Self {
+        OpenChuteError::Dxf(e)
+    }
+}
+
+impl From<printpdf::Error> for OpenChuteError {
+    fn from(e: printpdf::Error) -> Self {
+        OpenChuteError::Pdf(e)
+    }
+}
@@ -0,0 +1,225 @@
+// Versioning of the .chute file format.
+// Files are upgraded step by step as JSON before being deserialized, so the design structs
+// don't need to carry defaults for fields that older files are missing.
+
+use serde_json::{json, Value};
+
+use crate::error::OpenChuteError;
+use crate::materials::Cord;
+
+// Bump this and add a migration to MIGRATIONS whenever the file layout changes
+pub const FORMAT_VERSION: u64 = 10;
+
+// MIGRATIONS[n] upgrades a file from version n to n+1
+const MIGRATIONS: [fn(&mut Value); FORMAT_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+    migrate_v9_to_v10,
+];
+
+// Files without a format_version key are from before versioning was introduced (version 0)
+pub fn get_version(design: &Value) -> Result<u64, OpenChuteError> {
+    match design.get("format_version") {
+        None => Ok(0),
+        Some(version) => version.as_u64().ok_or_else(|| OpenChuteError::InvalidFormat(format!("format_version must be a positive integer, found {}", version))),
+    }
+}
+
+// Upgrade a design file to the current format version
+pub fn migrate(mut design: Value) -> Result<Value, OpenChuteError> {
+    if !design.is_object() {
+        return Err(OpenChuteError::InvalidFormat("Expected a JSON object".into()));
+    }
+
+    let version = get_version(&design)?;
+
+    if version > FORMAT_VERSION {
+        return Err(OpenChuteError::UnsupportedVersion(version));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut design);
+    }
+
+    design["format_version"] = json!(FORMAT_VERSION);
+    Ok(design)
+}
+
+// Sets a key on a JSON object if it's not already present
+fn insert_default(object: &mut Value, key: &str, value: Value) {
+    if let Some(map) = object.as_object_mut() {
+        map.entry(key).or_insert(value);
+    }
+}
+
+// Older files (including the examples/*_v2.chute generation) can miss the gore modifiers, cuts and fullness of each section
+fn migrate_v0_to_v1(design: &mut Value) {
+    if let Some(sections) = design.get_mut("chute_sections").and_then(Value::as_array_mut) {
+        for section in sections.iter_mut() {
+            insert_default(section, "modifier_first", json!("Nothing"));
+            insert_default(section, "modifier_last", json!("Nothing"));
+            insert_default(section, "cuts", json!([]));
+            insert_default(section, "fullness", json!([0.0, 0.0]));
+        }
+    }
+}
+
+// Nesting onto a fabric roll is off for older files
+fn migrate_v1_to_v2(design: &mut Value) {
+    insert_default(design, "fabric_width", Value::Null);
+}
+
+// Fabrics got a roll width (m) and price per meter
+fn migrate_v2_to_v3(design: &mut Value) {
+    let add_roll = |selector: &mut Value| {
+        if let Some(fabric) = selector.get_mut("selected_fabric") {
+            insert_default(fabric, "roll_width", json!(1.5));
+            insert_default(fabric, "price_per_meter", json!(0.0));
+        }
+        if let Some(options) = selector.get_mut("fabric_options").and_then(Value::as_array_mut) {
+            for fabric in options.iter_mut() {
+                insert_default(fabric, "roll_width", json!(1.5));
+                insert_default(fabric, "price_per_meter", json!(0.0));
+            }
+        }
+    };
+
+    if let Some(selector) = design.get_mut("fabric") {
+        add_roll(selector);
+    }
+    if let Some(sections) = design.get_mut("chute_sections").and_then(Value::as_array_mut) {
+        for section in sections.iter_mut() {
+            if let Some(selector) = section.get_mut("fabric") {
+                add_roll(selector);
+            }
+        }
+    }
+}
+
+// Mass estimation: suspension lines (one line per gore of the first section, the first default cord), seam tape and packing density
+fn migrate_v3_to_v4(design: &mut Value) {
+    let gores = design.get("chute_sections").and_then(|sections| sections.get(0)).and_then(|section| section.get("gores")).and_then(Value::as_u64).unwrap_or(8);
+    let cord = serde_json::to_value(&Cord::get_types()[0]).unwrap_or_default();
+
+    insert_default(design, "suspension_lines", json!({"count": gores, "length": 1.0, "cord": cord}));
+    insert_default(design, "tape_linear_density", json!(0.0));
+    insert_default(design, "pack_density", json!(500.0));
+}
+
+// Suspension line sizing: the line length became one of several modes, older files keep their fixed length.
+// Descent simulation settings are stored with the design
+fn migrate_v4_to_v5(design: &mut Value) {
+    if let Some(lines) = design.get_mut("suspension_lines") {
+        if let Some(length) = lines.get("length").filter(|length| length.is_number()).cloned() {
+            lines["length"] = json!({"Fixed": length});
+        }
+        insert_default(lines, "peak_load", json!(100.0));
+        insert_default(lines, "safety_factor", json!(2.0));
+    }
+    insert_default(design, "simulation", json!({"payload_mass": 1.0, "altitude": 300.0, "speed": 0.0, "cd": 0.8}));
+}
+
+// Canopy inflation in the descent simulation
+fn migrate_v5_to_v6(design: &mut Value) {
+    if let Some(simulation) = design.get_mut("simulation") {
+        insert_default(simulation, "fill_constant", json!(8.0));
+        insert_default(simulation, "area_growth_exponent", json!(2.0));
+    }
+}
+
+// The simulation became a sequence of stages. The single canopy of older files is a main stage using the design's canopy area
+fn migrate_v6_to_v7(design: &mut Value) {
+    if let Some(simulation) = design.get_mut("simulation").and_then(Value::as_object_mut) {
+        if !simulation.contains_key("stages") {
+            let mut stage = json!({"name": "Main", "area": null, "trigger": {"Time": 0.0}, "reefing": null});
+            for key in ["cd", "fill_constant", "area_growth_exponent"] {
+                if let Some(value) = simulation.remove(key) {
+                    stage[key] = value;
+                }
+            }
+            simulation.insert("stages".into(), json!([stage]));
+        }
+    }
+}
+
+// Older designs are not reefed
+fn migrate_v7_to_v8(design: &mut Value) {
+    insert_default(design, "reefing", Value::Null);
+}
+
+// Wind drift and landing dispersion. Older designs descend vertically
+fn migrate_v8_to_v9(design: &mut Value) {
+    if let Some(simulation) = design.get_mut("simulation") {
+        insert_default(simulation, "wind", Value::Null);
+        insert_default(simulation, "uncertainty", json!({"runs": 100, "cd": 0.1, "mass": 0.05, "wind_speed": 0.2, "wind_direction": 15.0}));
+    }
+}
+
+// Descent rate measurements from drop tests
+fn migrate_v9_to_v10(design: &mut Value) {
+    insert_default(design, "measurements", json!([]));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{migrate, FORMAT_VERSION};
+    use crate::error::OpenChuteError;
+    use crate::parachute::ChuteDesigner;
+
+    #[test]
+    fn test_load_examples() {
+        let examples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples");
+
+        for entry in std::fs::read_dir(examples).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "chute") {
+                assert!(ChuteDesigner::load_file(&path).is_ok(), "Unable to load {:?}", path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_migrate_v0() {
+        let design = json!({"chute_sections": [{"gores": 8}]});
+        let migrated = migrate(design).unwrap();
+
+        assert_eq!(migrated["format_version"], json!(FORMAT_VERSION));
+        assert_eq!(migrated["chute_sections"][0]["modifier_first"], json!("Nothing"));
+        assert_eq!(migrated["chute_sections"][0]["fullness"], json!([0.0, 0.0]));
+        assert_eq!(migrated["fabric_width"], json!(null));
+        assert_eq!(migrated["suspension_lines"]["count"], json!(8));
+        assert_eq!(migrated["suspension_lines"]["length"], json!({"Fixed": 1.0}));
+        assert_eq!(migrated["simulation"]["stages"][0]["cd"], json!(0.8));
+        assert_eq!(migrated["simulation"]["stages"][0]["fill_constant"], json!(8.0));
+        assert_eq!(migrated["reefing"], json!(null));
+        assert_eq!(migrated["simulation"]["wind"], json!(null));
+        assert_eq!(migrated["simulation"]["uncertainty"]["runs"], json!(100));
+        assert_eq!(migrated["measurements"], json!([]));
+    }
+
+    #[test]
+    fn test_migrate_fabric_roll() {
+        let fabric = json!({"area_density_gsm": 38.0, "name": "Ripstop nylon"});
+        let design = json!({"format_version": 2, "chute_sections": [{"fabric": {"modified": false, "selected_fabric": fabric, "fabric_options": [fabric]}}]});
+        let migrated = migrate(design).unwrap();
+
+        let selector = &migrated["chute_sections"][0]["fabric"];
+        assert_eq!(selector["selected_fabric"]["roll_width"], json!(1.5));
+        assert_eq!(selector["fabric_options"][0]["price_per_meter"], json!(0.0));
+    }
+
+    #[test]
+    fn test_newer_version() {
+        let design = json!({"format_version": FORMAT_VERSION + 1});
+        assert!(matches!(migrate(design), Err(OpenChuteError::UnsupportedVersion(_))));
+    }
+}
@@ -155,6 +155,64 @@ impl ToPoints for BezierSpline {
     }
 }
 
+// A free-hanging rope between two points. Sag is the depth at the middle, measured to the left of the direction from begin to end
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Catenary {
+    pub begin: Vector2<f64>,
+    pub end: Vector2<f64>,
+    pub sag: f64,
+}
+
+impl Catenary {
+    // Solve sag = a * (cosh(half_span / a) - 1) for the catenary parameter a
+    fn parameter(half_span: f64, sag: f64) -> f64 {
+        // Lower limit keeps cosh from overflowing
+        let (mut low, mut high) = (half_span / 700.0, half_span.max(sag) * 1e6);
+        for _ in 0..200 {
+            let a = (low * high).sqrt();
+            if a * ((half_span / a).cosh() - 1.0) > sag {
+                low = a;
+            } else {
+                high = a;
+            }
+        }
+        (low * high).sqrt()
+    }
+}
+
+impl ToPoints for Catenary {
+    fn to_points(&self, resolution: u32) -> Points {
+        let chord = self.end - self.begin;
+        let half_span = chord.norm() * 0.5;
+        let sag = self.sag.abs();
+
+        if half_span < 1e-12 || sag < 1e-12 {
+            return Points::from_vec(vec![self.begin, self.end]);
+        }
+
+        let along = chord / chord.norm();
+        let normal = Vector2::new(-along.y, along.x) * self.sag.signum();
+        let middle = (self.begin + self.end) * 0.5;
+        let a = Catenary::parameter(half_span, sag);
+
+        // Even number of steps puts a point at the deepest spot
+        let num_steps = ((resolution / 16) as usize).max(4) * 2;
+        let mut result = vec![];
+
+        for idx in 0..=num_steps {
+            let s = (idx as f64 / num_steps as f64 * 2.0 - 1.0) * half_span;
+            let depth = sag - a * ((s / a).cosh() - 1.0);
+            result.push(middle + along * s + normal * depth);
+        }
+
+        // Make the ends exact
+        result[0] = self.begin;
+        result[num_steps] = self.end;
+
+        Points::from_vec(result)
+    }
+}
+
 
 // A collection of points
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
@@ -193,6 +251,15 @@ impl Points {
         (min, max)
     }
 
+    // Length along the points. Closed adds the segment from the last point back to the first
+    pub fn length(&self, closed: bool) -> f64 {
+        let open_length: f64 = self.points.windows(2).map(|pair| (pair[1] - pair[0]).norm()).sum();
+        match (closed, self.points.first(), self.points.last()) {
+            (true, Some(first), Some(last)) => open_length + (first - last).norm(),
+            _ => open_length,
+        }
+    }
+
     pub fn mirror_x(&self) -> Self {
         // returns a copy mirrored around the X axis
         let mut new = self.clone();
@@ -210,6 +277,52 @@ impl Points {
         self.points.last().unwrap().clone()
     }
 
+    // Cut off the points above a line through origin, sloped by angle (in rad).
+    // Points are assumed to go upwards. The crossing point with the line becomes the new last point.
+    // Returns the number of original points that are kept
+    pub fn cut_above(&mut self, origin: Vector2<f64>, angle: f64) -> usize {
+        let height_above = |pt: &Vector2<f64>| pt.y - (origin.y + (pt.x - origin.x) * angle.tan());
+
+        match self.points.iter().position(|pt| height_above(pt) > 1e-9) {
+            None => self.points.len(),
+            Some(0) => {
+                // Everything is above the line, keep only the first point
+                self.points.truncate(1);
+                1
+            },
+            Some(idx) => {
+                let (pt0, pt1) = (self.points[idx - 1], self.points[idx]);
+                let (h0, h1) = (height_above(&pt0), height_above(&pt1));
+                self.points.truncate(idx);
+                self.points.push(pt0 + (pt1 - pt0) * (h0 / (h0 - h1)));
+                idx
+            }
+        }
+    }
+
+    // Cut off the points below a line through origin, sloped by angle (in rad).
+    // Points are assumed to go upwards. The crossing point with the line becomes the new first point.
+    // Returns the index of the first original point that is kept
+    pub fn cut_below(&mut self, origin: Vector2<f64>, angle: f64) -> usize {
+        let height_above = |pt: &Vector2<f64>| pt.y - (origin.y + (pt.x - origin.x) * angle.tan());
+
+        match self.points.iter().rposition(|pt| height_above(pt) < -1e-9) {
+            None => 0,
+            Some(idx) if idx == self.points.len() - 1 => {
+                // Everything is below the line, keep only the last point
+                self.points.drain(0..idx);
+                idx
+            },
+            Some(idx) => {
+                let (pt0, pt1) = (self.points[idx], self.points[idx + 1]);
+                let (h0, h1) = (height_above(&pt0), height_above(&pt1));
+                self.points.drain(0..=idx);
+                self.points.insert(0, pt0 + (pt1 - pt0) * (h0 / (h0 - h1)));
+                idx + 1
+            }
+        }
+    }
+
 }
 
 pub trait ToPoints {
@@ -235,7 +348,7 @@ mod tests {
 
     use nalgebra::Vector2;
 
-    use super::{EllipseArc, ToPoints, BezierSpline, polygon_to_circle_expansion};
+    use super::{EllipseArc, ToPoints, BezierSpline, Catenary, Points, polygon_to_circle_expansion};
 
     #[test]
     fn test_ellipse() {
@@ -265,6 +378,41 @@ mod tests {
         println!("{:?}", bez.to_points(50).points);
     }
 
+    #[test]
+    fn test_length() {
+        let square = Points::from_vec(vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0), Vector2::new(0.0, 1.0)]);
+        assert!((square.length(false) - 3.0).abs() < 1e-12);
+        assert!((square.length(true) - 4.0).abs() < 1e-12);
+        assert_eq!(Points::new().length(true), 0.0);
+    }
+
+    #[test]
+    fn test_cut() {
+        let mut pts = Points::from_vec(vec![Vector2::new(-1.0, 0.0), Vector2::new(-1.0, 1.0), Vector2::new(-1.0, 2.0)]);
+        // 45 degree line through (1, 2) crosses x=-1 at y=0
+        let kept = pts.clone().cut_above(Vector2::new(1.0, 2.0), PI / 4.0);
+        assert_eq!(kept, 1);
+
+        let kept = pts.cut_above(Vector2::new(1.0, 3.5), PI / 4.0);
+        assert_eq!(kept, 2);
+        assert!((pts.get_last_point() - Vector2::new(-1.0, 1.5)).norm() < 1e-9);
+
+        let first = pts.cut_below(Vector2::new(1.0, 2.5), PI / 4.0);
+        assert_eq!(first, 1);
+        assert!((pts.get_first_point() - Vector2::new(-1.0, 0.5)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_catenary() {
+        let cat = Catenary { begin: Vector2::new(1.0, 2.0), end: Vector2::new(-1.0, 2.0), sag: 0.5 };
+        let pts = cat.to_points(200);
+        let n = pts.points.len();
+        assert_eq!(pts.get_first_point(), Vector2::new(1.0, 2.0));
+        assert_eq!(pts.get_last_point(), Vector2::new(-1.0, 2.0));
+        // Sags to the left of the direction, which is downwards here
+        assert!((pts.points[n / 2] - Vector2::new(0.0, 1.5)).norm() < 1e-6);
+    }
+
     #[test]
     fn test_polygon_to_circle() {
         let test = polygon_to_circle_expansion(2);
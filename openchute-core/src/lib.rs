@@ -0,0 +1,17 @@
+// Parachute design engine: cross-section geometry, gore pattern generation, area calculations and DXF/PDF export.
+// Compiles without any GUI dependencies. Enable the "gui" feature for the egui editors and 3D mesh generation.
+
+#[macro_use]
+extern crate uom;
+
+pub mod error;
+pub mod file_format;
+pub mod geometry;
+pub mod parachute;
+pub mod pdf;
+mod nesting;
+pub mod configurable_shapes;
+pub mod materials;
+pub mod sim;
+#[cfg(feature = "gui")]
+pub mod ui;
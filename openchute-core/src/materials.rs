@@ -32,6 +32,29 @@ impl Cord {
         }
     }
 
+    pub fn get_linear_density(&self) -> f64 {
+        self.linear_density_g_per_m
+    }
+
+    pub fn get_strength(&self) -> f64 {
+        self.strength_newton
+    }
+
+    pub fn get_name_strength(&self, imperial: bool) -> String {
+        if imperial {
+            format!("{} ({:.0} lb)", self.name, self.strength_newton / LB_TO_N)
+        } else {
+            format!("{} ({:.0} N)", self.name, self.strength_newton)
+        }
+    }
+
+    // Lightest of the default cords that holds at least `strength_newton`
+    pub fn lightest_with_strength(strength_newton: f64) -> Option<Cord> {
+        Self::get_types().into_iter()
+            .filter(|cord| cord.strength_newton >= strength_newton)
+            .min_by(|a, b| a.linear_density_g_per_m.total_cmp(&b.linear_density_g_per_m))
+    }
+
     pub fn get_types() -> Vec<Cord> {
         // Default cord types
         
@@ -0,0 +1,217 @@
+// Nesting of pattern pieces onto a fabric roll. The roll runs along x, its width along y.
+// Pieces are placed on shelves across the roll width. Copies alternate between upright and rotated by 180°,
+// so tapered gores interlock instead of being spaced by their bounding boxes.
+
+use nalgebra::Vector2;
+
+use crate::error::OpenChuteError;
+use crate::parachute::{PatternLayout, PlacedPiece};
+
+// Height of the horizontal bands used for comparing piece outlines (m)
+const BAND_HEIGHT: f64 = 0.005;
+
+// Left and right edge of an outline within each band. Empty bands have min = inf, max = -inf
+struct BandExtents {
+    min: Vec<f64>,
+    max: Vec<f64>,
+}
+
+impl BandExtents {
+    fn new(contour: &[Vector2<f64>], bands: usize) -> Self {
+        let mut extents = Self { min: vec![f64::INFINITY; bands], max: vec![f64::NEG_INFINITY; bands] };
+
+        for (idx, p1) in contour.iter().enumerate() {
+            let p2 = contour[(idx + 1) % contour.len()];
+            let (low, high) = if p1.y <= p2.y { (*p1, p2) } else { (p2, *p1) };
+
+            let first = ((low.y / BAND_HEIGHT).floor().max(0.0) as usize).min(bands - 1);
+            let last = ((high.y / BAND_HEIGHT).floor().max(0.0) as usize).min(bands - 1);
+
+            // Clip the edge to each band it passes through
+            for band in first..=last {
+                let y0 = (band as f64 * BAND_HEIGHT).max(low.y);
+                let y1 = ((band + 1) as f64 * BAND_HEIGHT).min(high.y);
+                for y in [y0, y1] {
+                    let x = if high.y - low.y > 1e-12 { low.x + (high.x - low.x) * (y - low.y) / (high.y - low.y) } else { low.x.min(high.x) };
+                    extents.min[band] = extents.min[band].min(x);
+                    extents.max[band] = extents.max[band].max(x);
+                }
+                if high.y - low.y <= 1e-12 {
+                    extents.max[band] = extents.max[band].max(low.x.max(high.x));
+                }
+            }
+        }
+
+        extents
+    }
+}
+
+// Row of pieces across part of the roll width
+struct Shelf {
+    y: f64, // Bottom edge
+    height: f64,
+    right: Vec<f64>, // Right edge of the placed pieces in each band
+    rotate_next: bool, // Orientation alternates along the shelf
+}
+
+impl Shelf {
+    // Smallest x offset that keeps a piece `spacing` away from the pieces already on the shelf
+    fn offset_for(&self, piece: &BandExtents, spacing: f64) -> f64 {
+        let mut offset = f64::NEG_INFINITY;
+        let mut empty = true;
+
+        for (band, &right) in self.right.iter().enumerate() {
+            if let (Some(&min), true) = (piece.min.get(band), right.is_finite()) {
+                if min.is_finite() {
+                    offset = offset.max(right - min + spacing);
+                }
+            }
+            empty &= !right.is_finite();
+        }
+
+        if empty { spacing } else { offset.max(spacing) }
+    }
+}
+
+// Copy of a piece with its bounding box moved to the origin, optionally rotated by 180°
+fn normalized_copy(piece: &PlacedPiece, rotated: bool) -> PlacedPiece {
+    let size = piece.max - piece.min;
+    let transform = |pt: &Vector2<f64>| {
+        let local = pt - piece.min;
+        if rotated { size - local } else { local }
+    };
+    let transform_all = |contours: &Vec<Vec<Vector2<f64>>>| -> Vec<Vec<Vector2<f64>>> {
+        contours.iter().map(|contour| contour.iter().map(transform).collect()).collect()
+    };
+
+    PlacedPiece {
+        label: piece.label.clone(),
+        section: piece.section,
+        count: 1,
+        area: piece.area,
+        cut: transform_all(&piece.cut),
+        stitch: transform_all(&piece.stitch),
+        min: Vector2::zeros(),
+        max: size,
+    }
+}
+
+fn translated(mut piece: PlacedPiece, offset: Vector2<f64>) -> PlacedPiece {
+    for contour in piece.cut.iter_mut().chain(piece.stitch.iter_mut()) {
+        for pt in contour.iter_mut() {
+            *pt += offset;
+        }
+    }
+    piece.min += offset;
+    piece.max += offset;
+    piece
+}
+
+// Places `count` copies of every piece onto a roll of `fabric_width` (m). Pieces are separated by `spacing`.
+// The layout width is the required roll length
+pub(crate) fn nest(pieces: &[PlacedPiece], fabric_width: f64, spacing: f64) -> Result<PatternLayout, OpenChuteError> {
+    let mut order: Vec<&PlacedPiece> = pieces.iter().collect();
+    order.sort_by(|a, b| (b.max.y - b.min.y).total_cmp(&(a.max.y - a.min.y))); // Tallest first, they define the shelves
+
+    let mut shelves: Vec<Shelf> = vec![];
+    let mut layout = PatternLayout { pieces: vec![], width: 0.0, height: fabric_width };
+
+    for piece in order {
+        let height = piece.max.y - piece.min.y;
+        if height + 2.0 * spacing > fabric_width {
+            return Err(OpenChuteError::InvalidPattern(format!("Piece {} is {:.0} mm tall, which doesn't fit on a {:.0} mm wide fabric", piece.label, height * 1000.0, fabric_width * 1000.0)));
+        }
+        let bands = (height / BAND_HEIGHT).ceil() as usize + 1;
+
+        // Copy in the orientation the shelf needs next, with its x offset on the shelf
+        let fit = |shelf: &Shelf| {
+            let copy = normalized_copy(piece, shelf.rotate_next);
+            let extents = BandExtents::new(copy.cut.first().map_or(&[][..], |contour| contour.as_slice()), bands);
+            let offset = shelf.offset_for(&extents, spacing);
+            (copy, extents, offset)
+        };
+
+        for _ in 0..piece.count {
+            // Shelf where the piece ends up furthest left. Ties go to existing shelves
+            let mut best = None;
+            for (idx, shelf) in shelves.iter().enumerate().filter(|(_, shelf)| shelf.height >= height) {
+                let (copy, _, offset) = fit(shelf);
+                let end = offset + copy.max.x;
+                if best.is_none_or(|(_, best_end)| end < best_end) {
+                    best = Some((idx, end));
+                }
+            }
+
+            let next_y = shelves.last().map_or(spacing, |shelf| shelf.y + shelf.height + spacing);
+            let new_shelf_end = spacing + piece.max.x - piece.min.x;
+            let use_new_shelf = next_y + height + spacing <= fabric_width && best.is_none_or(|(_, end)| new_shelf_end < end);
+
+            let shelf_idx = if use_new_shelf {
+                shelves.push(Shelf { y: next_y, height, right: vec![], rotate_next: false });
+                shelves.len() - 1
+            } else if let Some((idx, _)) = best {
+                idx
+            } else {
+                return Err(OpenChuteError::InvalidPattern(format!("No room for piece {} on the fabric", piece.label)));
+            };
+
+            let (copy, extents, offset) = fit(&shelves[shelf_idx]);
+            let shelf = &mut shelves[shelf_idx];
+
+            if shelf.right.len() < bands {
+                shelf.right.resize(bands, f64::NEG_INFINITY);
+            }
+            for (band, &max) in extents.max.iter().enumerate() {
+                if max.is_finite() {
+                    shelf.right[band] = shelf.right[band].max(max + offset);
+                }
+            }
+            shelf.rotate_next = !shelf.rotate_next;
+
+            let placed = translated(copy, Vector2::new(offset, shelf.y));
+            layout.width = layout.width.max(placed.max.x + spacing);
+            layout.pieces.push(placed);
+        }
+    }
+
+    Ok(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector2;
+
+    use super::nest;
+    use crate::parachute::PlacedPiece;
+
+    fn triangle(count: u16) -> PlacedPiece {
+        let outline = vec![Vector2::new(0.0, 0.0), Vector2::new(0.2, 0.0), Vector2::new(0.1, 1.0)];
+        PlacedPiece { label: "#1".into(), section: 0, count, area: 0.1, cut: vec![outline.clone()], stitch: vec![outline], min: Vector2::zeros(), max: Vector2::new(0.2, 1.0) }
+    }
+
+    #[test]
+    fn test_nest_interlocks() {
+        let layout = nest(&[triangle(8)], 1.1, 0.01).unwrap();
+        assert_eq!(layout.pieces.len(), 8);
+
+        // Alternating triangles take about half the length of their bounding boxes
+        assert!(layout.width < 8.0 * 0.2 * 0.75, "Roll length {}", layout.width);
+        assert!(layout.utilization() > 0.5);
+
+        // Pieces stay on the fabric
+        for placed in &layout.pieces {
+            assert!(placed.min.y >= 0.0 && placed.max.y <= 1.1);
+        }
+    }
+
+    #[test]
+    fn test_nest_shelves() {
+        // Two rows fit across a wide roll
+        let layout = nest(&[triangle(8)], 2.2, 0.01).unwrap();
+        assert!(layout.pieces.iter().any(|placed| placed.min.y > 1.0));
+        assert!(layout.width < 4.0 * 0.2 * 0.75);
+
+        // Too narrow
+        assert!(nest(&[triangle(1)], 0.5, 0.01).is_err());
+    }
+}
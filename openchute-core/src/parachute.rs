@@ -0,0 +1,4289 @@
+#![allow(unused)]
+
+use std::hash::{Hash, Hasher};
+extern crate nalgebra as na;
+use std::default;
+use std::path::{Path, PathBuf};
+
+use dxf;
+use dxf::Drawing;
+use dxf::entities::*;
+#[cfg(feature = "gui")]
+use eframe::egui;
+#[cfg(feature = "gui")]
+use egui_plot;
+use evalexpr::ContextWithMutableVariables;
+use uom::unit;
+use crate::parachute;
+#[cfg(feature = "gui")]
+use crate::ui;
+use crate::geometry;
+use crate::error::OpenChuteError;
+use crate::file_format;
+use crate::materials::Cord;
+use crate::sim;
+
+use nalgebra::Vector2;
+
+use std::f64::consts::PI;
+
+use uom::si::{self, length};
+
+use super::configurable_shapes::ConfigurableGeometry;
+use super::geometry::ToPoints;
+use super::configurable_shapes;
+#[cfg(feature = "gui")]
+use super::ui::integer_edit_field;
+use geometry::vec2;
+
+use serde::{Serialize, Deserialize};
+use serde_json;
+
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub enum GeometryType {
+    Line(configurable_shapes::ConfigurableLine),
+    EllipseArc(configurable_shapes::ConfigurableEllipse),
+    PointList(configurable_shapes::ConfigurablePointList),
+    DxfProfile(configurable_shapes::ConfigurableDxfProfile),
+}
+
+impl geometry::ToPoints for GeometryType {
+    fn to_points(&self, resolution: u32) -> geometry::Points {
+        match self {
+            Self::Line(config) => config.to_points(resolution),
+            Self::EllipseArc(config) => config.to_points(resolution),
+            Self::PointList(config) => config.to_points(resolution),
+            Self::DxfProfile(config) => config.to_points(resolution),
+        }
+    }
+}
+
+impl GeometryType {
+    fn update_from_context(&mut self, evaluator_context: &evalexpr::HashMapContext) {
+        match self {
+            Self::Line(config) => config.update_from_context(evaluator_context),
+            Self::EllipseArc(config) => config.update_from_context(evaluator_context),
+            Self::PointList(config) => config.update_from_context(evaluator_context),
+            Self::DxfProfile(config) => config.update_from_context(evaluator_context),
+        }
+    }
+}
+
+// Represents a band. Can contain multiple geometries representing the cross section, but is symmetrical and has a fixed number of gores
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct PolygonalChuteSection {
+    objects: Vec<GeometryType>,
+
+}
+
+impl PolygonalChuteSection {
+    fn add_line(&mut self) {
+        self.objects.push(GeometryType::Line(
+            configurable_shapes::ConfigurableLine::new(),
+        ));
+    }
+
+    fn add_ellipse(&mut self) {
+        self.objects.push(GeometryType::EllipseArc(
+            configurable_shapes::ConfigurableEllipse::new(),
+        ));
+    }
+
+    fn add_pointlist(&mut self) {
+        self.objects.push(GeometryType::PointList(
+            configurable_shapes::ConfigurablePointList::new(),
+        ));
+    }
+
+    fn add_dxf_profile(&mut self) {
+        self.objects.push(GeometryType::DxfProfile(
+            configurable_shapes::ConfigurableDxfProfile::new(),
+        ));
+    }
+
+    fn update_from_context(&mut self, evaluator_context: &evalexpr::HashMapContext) {
+        for object in self.objects.iter_mut() {
+            object.update_from_context(evaluator_context);
+        }
+    }
+}
+
+impl Default for PolygonalChuteSection {
+    fn default() -> Self {
+        Self { objects: vec![] }
+    }
+}
+
+// Represents a chute section that can be bent into a perfectly circular disk/cone/cylinder. Only a straight line allowed
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct CircularChuteSection {
+    line: geometry::Line,
+    expressions: [String; 4]
+}
+
+impl Default for CircularChuteSection {
+    fn default() -> Self {
+        Self {
+            line: geometry::Line { begin: Vector2::new(0.0, 0.0), end: Vector2::new(1.0, 0.0)},
+            expressions: ["0".to_string(), "0".to_string(), "1.0".to_string(), "0.0".to_string()].into()
+        }
+    }
+}
+
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub enum ChuteSectionType {
+    Polygonal(PolygonalChuteSection),
+    Circular(CircularChuteSection)
+}
+
+
+// Steepest cut allowed for GoreModifier::SlantAngle, avoids infinite slopes
+const MAX_SLANT_ANGLE: f64 = 89.0 * PI / 180.0;
+
+// Common width of ripstop nylon rolls (m), used when nesting is switched on
+const DEFAULT_FABRIC_WIDTH: f64 = 1.5;
+
+// Hand packed nylon canopies are around 0.5 g/cm3
+const DEFAULT_PACK_DENSITY: f64 = 500.0;
+
+// Catenary from begin to end, sagging down to the height of through_point
+fn catenary_through(begin: Vector2<f64>, end: Vector2<f64>, through_point: Vector2<f64>, resolution: u32) -> Vec<Vector2<f64>> {
+    let chord = end - begin;
+    let left_normal = geometry::vec2(-chord.y, chord.x) / chord.norm().max(1e-12);
+    let sag = (through_point - (begin + end) * 0.5).dot(&left_normal);
+    geometry::Catenary { begin, end, sag }.to_points(resolution).points
+}
+
+// For stuff like assymetrical gore top/bottom or cutouts
+#[derive(Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum GoreModifier {
+    #[default]
+    Nothing, // Don't modify
+    SlantSegmentLeft, // Slant whole segment
+    SlantSegmentRight, // Slant whole segment
+    TriangleSegmentIn, // Triangle pointing inwards
+    TriangleSegmentOut, // Triangle pointing outwards
+    Catenary, // Arch formed by free-hanging rope https://en.wikipedia.org/wiki/Catenary
+    SlantAngle(f64) // Slant by defined angle (in rad)
+}
+
+#[cfg(feature = "gui")]
+impl GoreModifier {
+    fn selector(ui: &mut egui::Ui, frame: &mut eframe::Frame, modifier: &mut GoreModifier, id: u16) {
+        ui.horizontal(|ui| {
+
+            egui::ComboBox::from_id_source(id)
+                .width(200.0)
+                .selected_text(modifier.to_string())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(modifier, GoreModifier::Nothing, "None");
+                    ui.selectable_value(modifier, GoreModifier::SlantSegmentLeft, "Slant segment left");
+                    ui.selectable_value(modifier, GoreModifier::SlantSegmentRight, "Slant segment right");
+                    ui.selectable_value(modifier, GoreModifier::TriangleSegmentIn, "Triangle segment inwards");
+                    ui.selectable_value(modifier, GoreModifier::TriangleSegmentOut, "Triangle segment outwards");
+                    ui.selectable_value(modifier, GoreModifier::Catenary, "Catenary curve");
+                    ui.selectable_value(modifier, GoreModifier::SlantAngle(std::f64::consts::FRAC_PI_4), "Slant by angle");
+                }
+            );
+
+            match modifier {
+                GoreModifier::SlantAngle(angle) => {
+                    ui::length_slider_no_limit(ui, angle, false, -std::f64::consts::FRAC_PI_2..=std::f64::consts::FRAC_PI_2, &si::angle::degree, &si::angle::degree);
+
+                },
+                _ => {}
+            }
+
+        });
+    }
+}
+
+impl core::fmt::Display for GoreModifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoreModifier::Nothing => write!(f, "None"),
+            GoreModifier::SlantSegmentLeft => write!(f, "Slant segment left"),
+            GoreModifier::SlantSegmentRight => write!(f, "Slant segment right"),
+            GoreModifier::SlantAngle(_) => write!(f, "Slant with angle"),
+            GoreModifier::TriangleSegmentIn => write!(f, "Triangle segment inwards"),
+            GoreModifier::TriangleSegmentOut => write!(f, "Triangle segment outwards"),
+            GoreModifier::Catenary => write!(f, "Catenary curve")
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChuteSection {
+    section_type: ChuteSectionType,
+    gores: u16,
+    fabric: FabricSelector,
+    seam_allowance: (f64, f64, f64, f64), // Right, top, left, bottom
+    corner_cutout: bool,
+    colors: Vec<[f32; 3]>, // Colors. If less than number of gores, it continues repeating
+    modifier_first: GoreModifier, // Modifier for first thing in list
+    modifier_last: GoreModifier, // Modifier for last thing in list
+    //expansion_first: f64, // Expansion of the gore (not reflected in 3D)
+    //expansion_last: f64, // Expansion of the gore
+    cuts: Vec<(f64, f64)>, // Cuts given in vertical ratio (0-1) and angle (in rad)
+    fullness: (f64, f64), // Leading/trailing edge fullness. Take leading edge of gore and expand by this amount
+}
+
+#[cfg(feature = "gui")]
+impl ChuteSection {
+
+    fn simple_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, use_imperial: bool, evaluator_context: &evalexpr::HashMapContext, index_id: u16) {
+        ui.heading(match self.section_type {
+            ChuteSectionType::Circular(_) => "Circular band",
+            ChuteSectionType::Polygonal(_) => "Polygonal geometry",
+        });
+        
+        ui.label("Fabric:");
+        self.fabric.ui(ui, frame, use_imperial, index_id);
+        
+        // Seam allowance input
+        ui.label("Seam allowance (right, left)");
+        ui.horizontal(|ui| {
+            ui::length_slider(ui, &mut self.seam_allowance.0, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+            ui::length_slider(ui, &mut self.seam_allowance.2, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+        });
+        ui.label("Seam allowance (top, bottom)");
+        ui.horizontal(|ui| {
+            ui::length_slider(ui, &mut self.seam_allowance.1, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+            ui::length_slider(ui, &mut self.seam_allowance.3, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+        });
+
+        ui.checkbox(&mut self.corner_cutout, "Cut out seam allowance corners");
+
+        ui.label("Number of gores:").on_hover_text("Number of parachute gores. Typically between 6 and 24");
+        ui::integer_edit_field(ui, &mut self.gores);
+
+        //ui.label("Shape modifier first");
+        //GoreModifier::selector(ui, frame, &mut self.modifier_first, index_id * 2);
+
+        //ui.label("Shape modifier last");
+        //GoreModifier::selector(ui, frame, &mut self.modifier_last, index_id*2 + 1);
+
+
+        ui.horizontal(|ui| {
+            ui.label("Color:");
+            if ui.button("➕").clicked() {
+                // Alternate between international orange and black
+                if self.colors.len() % 2 == 0 {
+                    self.colors.push([1.0, 0.31, 0.0]);
+                } else {
+                    self.colors.push([0.0, 0.0, 0.0]);
+                }
+            };
+
+            if ui.add_enabled(self.colors.len() > 0, egui::Button::new("➖")).clicked() {
+                self.colors.pop().unwrap_or_default();
+            }
+
+            for color in self.colors.iter_mut() {
+                ui.color_edit_button_rgb(color);
+            }
+        });
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, use_imperial: bool, evaluator_context: &evalexpr::HashMapContext, index_id: u16) {
+        ui.heading(match self.section_type {
+            ChuteSectionType::Circular(_) => "Circular band",
+            ChuteSectionType::Polygonal(_) => "Polygonal geometry",
+        });
+        
+        ui.label("Fabric:");
+        self.fabric.ui(ui, frame, use_imperial, index_id);
+        
+        // Seam allowance input
+        ui.label("Seam allowance (right, left)");
+        ui.horizontal(|ui| {
+            ui::length_slider(ui, &mut self.seam_allowance.0, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+            ui::length_slider(ui, &mut self.seam_allowance.2, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+        });
+        ui.label("Seam allowance (top, bottom)");
+        ui.horizontal(|ui| {
+            ui::length_slider(ui, &mut self.seam_allowance.1, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+            ui::length_slider(ui, &mut self.seam_allowance.3, use_imperial, 0.0..=0.1, &length::millimeter, &length::inch);
+        });
+        ui.label("Fullness expansion (Leading, Trailing)");
+        ui.horizontal(|ui| {
+            ui::length_slider(ui, &mut self.fullness.0, use_imperial, 0.0..=0.5, &unitless, &unitless);
+            ui::length_slider(ui, &mut self.fullness.1, use_imperial, 0.0..=0.5, &unitless, &unitless);
+        });
+
+        ui.checkbox(&mut self.corner_cutout, "Cut out seam allowance corners");
+
+        ui.label("Number of gores:").on_hover_text("Number of parachute gores. Typically between 6 and 24");
+        ui::integer_edit_field(ui, &mut self.gores);
+
+        ui.label("Shape modifier first");
+        GoreModifier::selector(ui, frame, &mut self.modifier_first, index_id * 2);
+
+        ui.label("Shape modifier last");
+        GoreModifier::selector(ui, frame, &mut self.modifier_last, index_id*2 + 1);
+
+
+        ui.horizontal(|ui| {
+            ui.label("Color:");
+            if ui.button("➕").clicked() {
+                // Alternate between international orange and black
+                if self.colors.len() % 2 == 0 {
+                    self.colors.push([1.0, 0.31, 0.0]);
+                } else {
+                    self.colors.push([0.0, 0.0, 0.0]);
+                }
+            };
+
+            if ui.add_enabled(self.colors.len() > 0, egui::Button::new("➖")).clicked() {
+                self.colors.pop().unwrap_or_default();
+            }
+
+            for color in self.colors.iter_mut() {
+                ui.color_edit_button_rgb(color);
+            }
+        });
+
+        ui.separator();
+
+        let eval = |expr: &str| evalexpr::eval_number_with_context(expr, evaluator_context).unwrap_or(0.0);
+
+        match &mut self.section_type {
+            ChuteSectionType::Circular(sec) => {
+                ui.label("Circular chute section:");
+                ui.label("Start point:");
+
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut sec.expressions[0]).clip_text(false).desired_width(200.0));
+                    ui.add(egui::TextEdit::singleline(&mut sec.expressions[1]).clip_text(false).desired_width(200.0));
+                });
+
+                ui.label("End point:");
+
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut sec.expressions[2]).clip_text(false).desired_width(200.0));
+                    ui.add(egui::TextEdit::singleline(&mut sec.expressions[3]).clip_text(false).desired_width(200.0));
+                });
+
+                sec.line.begin.x = eval(&sec.expressions[0]);
+                sec.line.begin.y = eval(&sec.expressions[1]);
+                sec.line.end.x = eval(&sec.expressions[2]);
+                sec.line.end.y = eval(&sec.expressions[3]);
+
+            },
+            ChuteSectionType::Polygonal(sec) => {
+                ui.horizontal(|ui| {
+                    if ui.button("Add line").clicked() {
+                        sec.add_line();
+                    }
+
+                    if ui.button("Add ellipse arc").clicked() {
+                        sec.add_ellipse();
+                    }
+
+                    if ui.button("Add point list").clicked() {
+                        sec.add_pointlist();
+                    }
+
+                    if ui.button("Add DXF profile").clicked() {
+                        sec.add_dxf_profile();
+                    }
+                });
+
+                ui.separator();
+
+                let mut to_delete: Option<usize> = None;
+                let mut to_move: Option<(usize, bool)> = None;
+                let num_parameters = sec.objects.len();
+
+                for (idx, object) in sec.objects.iter_mut().enumerate() {
+
+                    egui::Frame::none().stroke(egui::Stroke::new(1.0, egui::Color32::GRAY)).inner_margin(10.0).outer_margin(5.0).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui::delete_move_buttons(ui, &mut to_delete, &mut to_move, idx, num_parameters);
+                        });
+                        match object {
+                            GeometryType::Line(config) => config.ui(ui, frame, use_imperial, evaluator_context),
+                            GeometryType::EllipseArc(config) => config.ui(ui, frame, use_imperial, evaluator_context),
+                            GeometryType::PointList(config) => config.ui(ui, frame, use_imperial, evaluator_context),
+                            GeometryType::DxfProfile(config) => config.ui(ui, frame, use_imperial, evaluator_context),
+                        }
+                    });
+                }
+
+                if let Some(delete_idx) = to_delete {
+                    sec.objects.remove(delete_idx);
+                }
+                if let Some((idx, direction)) = to_move {
+                    if idx > 0 && direction {
+                        // Swap upwards
+                        sec.objects.swap(idx, idx-1);
+                    } else if idx < (sec.objects.len() - 1) && !direction{
+                        sec.objects.swap(idx, idx+1);
+                    }
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label("Cuts (height, angle):").on_hover_text("Split each gore into separate pattern pieces, e.g. for ringslot and ringsail designs. Height is a fraction of the gore height");
+                    if ui.button("➕").clicked() {
+                        self.cuts.push((0.5, 0.0));
+                    }
+                });
+
+                let mut to_delete: Option<usize> = None;
+
+                for (idx, cut) in self.cuts.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button("❌").on_hover_text("Delete").clicked() {
+                            to_delete = Some(idx);
+                        }
+                        ui::length_slider(ui, &mut cut.0, use_imperial, 0.0..=1.0, &unitless, &unitless);
+                        ui::length_slider_no_limit(ui, &mut cut.1, false, -std::f64::consts::FRAC_PI_4..=std::f64::consts::FRAC_PI_4, &si::angle::degree, &si::angle::degree);
+                    });
+                }
+
+                if let Some(delete_idx) = to_delete {
+                    self.cuts.remove(delete_idx);
+                }
+            }
+        }
+    }
+
+}
+
+impl ChuteSection {
+    pub fn update_from_context(&mut self, evaluator_context: &evalexpr::HashMapContext) {
+        let eval = |expr: &str| evalexpr::eval_number_with_context(expr, evaluator_context).unwrap_or(0.0);
+        match &mut self.section_type {
+            ChuteSectionType::Circular(sec) => {
+                sec.line.begin.x = eval(&sec.expressions[0]);
+                sec.line.begin.y = eval(&sec.expressions[1]);
+                sec.line.end.x = eval(&sec.expressions[2]);
+                sec.line.end.y = eval(&sec.expressions[3]);
+            },
+            ChuteSectionType::Polygonal(sec) => {
+                sec.update_from_context(evaluator_context);
+            }
+        }
+    }
+
+    fn new_circular() -> Self {
+        Self {
+            section_type: ChuteSectionType::Circular(CircularChuteSection::default()),
+            gores: 8,
+            fabric: FabricSelector::new(),
+            seam_allowance: (0.01, 0.01, 0.01, 0.01),
+            colors: vec![[1.0, 0.31, 0.0], [0.0, 0.0, 0.0]],
+            corner_cutout: false,
+            modifier_first: GoreModifier::Nothing,
+            modifier_last: GoreModifier::Nothing,
+            cuts: vec![],
+            fullness: (0.0, 0.0)
+        }
+    }
+
+    fn new_polygonal() -> Self {
+        Self {
+            section_type: ChuteSectionType::Polygonal(PolygonalChuteSection::default()),
+            gores: 8,
+            fabric: FabricSelector::new(),
+            seam_allowance: (0.01, 0.01, 0.01, 0.01),
+            colors: vec![[1.0, 0.31, 0.0], [0.0, 0.0, 0.0]],
+            corner_cutout: false,
+            modifier_first: GoreModifier::Nothing,
+            modifier_last: GoreModifier::Nothing,
+            cuts: vec![],
+            fullness: (0.0, 0.0),
+        }
+    }
+
+    fn get_cross_section(&self, resolution: u32, expanded_polygon: bool) -> geometry::Points {
+        // expanded_polygon expands the polygonal geometry so the circumference is equal the nominal circular circumference.
+        // Not used in 2D plot, only when generating gores or in 3D plot (corner locations)
+
+        // to 2D section
+        match &self.section_type {
+            ChuteSectionType::Circular(circ) => {
+                circ.line.to_points(resolution)
+            },
+            ChuteSectionType::Polygonal(poly) => {
+                let mut pts = geometry::Points::new();
+
+                for object in &poly.objects {
+                    if expanded_polygon {
+                        let mut cross_section = object.to_points(resolution).points;
+                        let expansion_ratio = geometry::polygon_to_circle_expansion(self.gores);
+                        cross_section.iter_mut().for_each(|pt| {
+                            pt.x *= expansion_ratio;
+                        });
+
+                        pts.points.append(&mut cross_section);
+                    } else {
+                        pts.points.append(&mut object.to_points(resolution).points);
+                    }
+                }
+
+                pts
+            }
+        }
+    }
+
+    fn get_cross_section_with_indices(&self, resolution: u32, expanded_polygon: bool) -> (geometry::Points, Vec<usize>) {
+        // to 2D section
+        let mut segment_indices = vec![];
+
+        match &self.section_type {
+            ChuteSectionType::Circular(circ) => {
+                (circ.line.to_points(resolution), segment_indices)
+            },
+            ChuteSectionType::Polygonal(poly) => {
+                let mut pts = geometry::Points::new();
+
+                for object in &poly.objects {
+                    segment_indices.push(pts.points.len());
+                    if expanded_polygon {
+                        let mut cross_section = object.to_points(resolution).points;
+                        let expansion_ratio = geometry::polygon_to_circle_expansion(self.gores);
+                        cross_section.iter_mut().for_each(|pt| {
+                            pt.x *= expansion_ratio;
+                        });
+
+                        pts.points.append(&mut cross_section);
+                    } else {
+                        pts.points.append(&mut object.to_points(resolution).points);
+                    }
+                }
+
+                segment_indices.push(pts.points.len()); // Last one
+                (pts, segment_indices)
+            }
+        }
+    }
+
+    // Right and left edge of a polygonal gore before any gore modifiers, with fullness applied.
+    // Both edges go from the skirt towards the top, and have one point per cross section point.
+    fn get_gore_edges(&self, resolution: u32) -> Option<(geometry::Points, geometry::Points, Vec<usize>)> {
+        if self.gores < 2 {
+            // Not possible with polygonal shape
+            return None;
+        }
+
+        let (mut cross_section, sec_indices) = self.get_cross_section_with_indices(resolution, true);
+
+        if cross_section.points.len() < 2 {
+            // Must have at least two points
+            return None;
+        }
+        // Duplicate last point
+        cross_section.points.push(cross_section.points.last().unwrap().clone());
+
+        let mut right_points = geometry::Points::new();
+
+        // Assume for now that points start from skirt and go towards top.
+        let mut y_coord = 0.0;
+
+        let polygon_side_distance = geometry::polygon_center_to_side(self.gores);
+        let polygon_side_length = geometry::polygon_edge_len(self.gores);
+
+        for idx in 0..cross_section.points.len()-1 {
+            let this_pt = cross_section.points.get(idx).unwrap();
+            let next_pt = cross_section.points.get(idx+1).unwrap();
+            let diff = (((this_pt.x - next_pt.x) * polygon_side_distance).powi(2) + (this_pt.y - next_pt.y).powi(2)).sqrt();
+            let gore_pt = vec2(polygon_side_length * this_pt.x * 0.5, y_coord);
+            right_points.points.push(gore_pt);
+            y_coord += diff;
+        }
+
+        // Fullness expansion calculation. Start by getting y coords of first and last point.
+        let y_first = right_points.points.first().unwrap().y;
+        let y_last = right_points.points.last().unwrap().y;
+        let fullness_slope = (self.fullness.1 - self.fullness.0) / (y_last - y_first);
+        let y_to_expansion = |y| self.fullness.0 + 1.0 + fullness_slope * (y-y_first);
+
+        // Linearly interpolate fullness between the two
+        for pt in right_points.points.iter_mut() {
+            pt.x *= y_to_expansion(pt.y);
+        }
+
+        let left_points = right_points.mirror_x();
+
+        Some((right_points, left_points, sec_indices))
+    }
+
+    // Outline of a flat polygonal gore with the gore modifiers applied, without seam allowance.
+    // Returns the right, top, left and bottom sides, going counterclockwise starting at the bottom right corner
+    fn get_gore_outline(&self, resolution: u32) -> Option<[Vec<Vector2<f64>>; 4]> {
+        let (mut right_points, mut left_points, sec_indices) = self.get_gore_edges(resolution)?;
+
+        let num_gore_points = right_points.points.len();
+
+        let mut top_points = None;
+        let mut bottom_points = None;
+
+        if sec_indices.len() > 1 { // At least one valid segment (two pairs)
+            // Delete points to modify the shape
+            match &self.modifier_last {
+                // Last points. Drain these first
+                GoreModifier::Nothing => {},
+                GoreModifier::SlantSegmentLeft => {
+                    if let Some(idx) = sec_indices.get(sec_indices.len()-2) {
+                        // +1 keeps the first point
+                        left_points.points.drain((idx.clone() + 1 as usize).min(num_gore_points)..num_gore_points);
+                    }
+                },
+                GoreModifier::SlantSegmentRight => {
+                    if let Some(idx) = sec_indices.get(sec_indices.len()-2) {
+                        right_points.points.drain((idx.clone() + 1 as usize).min(num_gore_points)..num_gore_points);
+                    }
+                },
+                GoreModifier::SlantAngle(angle) => {
+                    // Positive angle keeps the right corner and cuts downwards towards the left
+                    let angle = angle.clamp(-MAX_SLANT_ANGLE, MAX_SLANT_ANGLE);
+                    if angle >= 0.0 {
+                        left_points.cut_above(right_points.get_last_point(), angle);
+                    } else {
+                        right_points.cut_above(left_points.get_last_point(), angle);
+                    }
+                },
+                GoreModifier::TriangleSegmentIn => {
+                    if let Some(idx) = sec_indices.get(sec_indices.len()-2) {
+                        let middle_point_idx = (idx.clone()).min(num_gore_points-1);
+                        let middle_point = (left_points.points[middle_point_idx] + right_points.points[middle_point_idx]) * 0.5;
+                        top_points = Some(vec![right_points.get_last_point(), middle_point, left_points.get_last_point()]);
+                    }
+                },
+                GoreModifier::TriangleSegmentOut => {
+                    // Both edges end at the start of the last segment, the tip is at the top center
+                    if let Some(idx) = sec_indices.get(sec_indices.len()-2) {
+                        let tip_point = (left_points.get_last_point() + right_points.get_last_point()) * 0.5;
+                        left_points.points.drain((*idx + 1).min(num_gore_points)..num_gore_points);
+                        right_points.points.drain((*idx + 1).min(num_gore_points)..num_gore_points);
+                        top_points = Some(vec![right_points.get_last_point(), tip_point, left_points.get_last_point()]);
+                    }
+                },
+                GoreModifier::Catenary => {
+                    // Same depth as the inwards triangle
+                    if let Some(idx) = sec_indices.get(sec_indices.len()-2) {
+                        let middle_point_idx = (*idx).min(num_gore_points-1);
+                        let middle_point = (left_points.points[middle_point_idx] + right_points.points[middle_point_idx]) * 0.5;
+                        let begin = right_points.get_last_point();
+                        let end = left_points.get_last_point();
+                        top_points = Some(catenary_through(begin, end, middle_point, resolution));
+                    }
+                },
+            }
+            
+            match &self.modifier_first {
+                // First points. Drain these afterwards
+                GoreModifier::Nothing => {},
+                GoreModifier::SlantSegmentLeft => {
+                    if let Some(idx) = sec_indices.get(1) {
+                        let end = idx.clone().min(right_points.points.len().max(1) - 1);
+                        right_points.points.drain(0..end);
+                    }
+                },
+                GoreModifier::SlantSegmentRight => {
+                    if let Some(idx) = sec_indices.get(1) {
+                        let end = idx.clone().min(left_points.points.len().max(1) - 1);
+                        left_points.points.drain(0..end);
+                    }
+                },
+                GoreModifier::SlantAngle(angle) => {
+                    // Positive angle keeps the left corner and cuts upwards towards the right
+                    let angle = angle.clamp(-MAX_SLANT_ANGLE, MAX_SLANT_ANGLE);
+                    if angle >= 0.0 {
+                        right_points.cut_below(left_points.get_first_point(), angle);
+                    } else {
+                        left_points.cut_below(right_points.get_first_point(), angle);
+                    }
+                },
+                GoreModifier::TriangleSegmentIn => {
+                    if let Some(idx) = sec_indices.get(1) {
+                        let middle_point_idx = (idx.clone()).min(left_points.points.len().max(1) - 1);
+                        let middle_point = (left_points.points[middle_point_idx] + right_points.points[middle_point_idx]) * 0.5;
+                        bottom_points = Some(vec![left_points.get_first_point(), middle_point, right_points.get_first_point()]);
+                    }
+                },
+                GoreModifier::TriangleSegmentOut => {
+                    // Both edges start at the end of the first segment, the tip is at the bottom center
+                    if let Some(idx) = sec_indices.get(1) {
+                        let tip_point = (left_points.get_first_point() + right_points.get_first_point()) * 0.5;
+                        let end = (*idx).min(left_points.points.len().max(1) - 1).min(right_points.points.len().max(1) - 1);
+                        left_points.points.drain(0..end);
+                        right_points.points.drain(0..end);
+                        bottom_points = Some(vec![left_points.get_first_point(), tip_point, right_points.get_first_point()]);
+                    }
+                },
+                GoreModifier::Catenary => {
+                    if let Some(idx) = sec_indices.get(1) {
+                        let middle_point_idx = (*idx).min(left_points.points.len().max(1) - 1);
+                        let middle_point = (left_points.points[middle_point_idx] + right_points.points[middle_point_idx]) * 0.5;
+                        let begin = left_points.get_first_point();
+                        let end = right_points.get_first_point();
+                        bottom_points = Some(catenary_through(begin, end, middle_point, resolution));
+                    }
+                },
+            }
+        }
+
+        left_points.points.reverse();
+        let top_pts = top_points.unwrap_or(vec![right_points.get_last_point(), left_points.get_first_point()]);
+        let bottom_pts = bottom_points.unwrap_or(vec![left_points.get_last_point(), right_points.get_first_point()]);
+
+        Some([right_points.points, top_pts, left_points.points, bottom_pts])
+    }
+
+    // Range of cross section points covered by the modified gore, for evenly spaced columns across the flat gore.
+    // Column 0 is the left edge and the last column is the right edge of the pattern
+    fn get_gore_index_limits(&self, resolution: u32, num_columns: usize) -> Vec<(usize, usize)> {
+        let (Some((edge, _, _)), Some([_, top, _, bottom])) = (self.get_gore_edges(resolution), self.get_gore_outline(resolution)) else {
+            return vec![];
+        };
+
+        let last_idx = edge.points.len() - 1;
+
+        // Fractional cross section index at a height in the pattern
+        let y_to_index = |y: f64| -> f64 {
+            match edge.points.iter().position(|pt| pt.y >= y) {
+                None => last_idx as f64,
+                Some(0) => 0.0,
+                Some(idx) => {
+                    let (y0, y1) = (edge.points[idx - 1].y, edge.points[idx].y);
+                    (idx - 1) as f64 + (y - y0) / (y1 - y0).max(1e-12)
+                }
+            }
+        };
+
+        // Position across the gore, 0 at the left edge and 1 at the right edge
+        let to_column_coords = |pt: &Vector2<f64>| -> (f64, f64) {
+            let index = y_to_index(pt.y);
+            let (idx0, idx1) = (index.floor() as usize, (index.ceil() as usize).min(last_idx));
+            let half_width = edge.points[idx0].x + (edge.points[idx1].x - edge.points[idx0].x) * (index - idx0 as f64);
+            let across = if half_width.abs() < 1e-12 { 0.5 } else { 0.5 + pt.x / (2.0 * half_width) };
+            (across.clamp(0.0, 1.0), index)
+        };
+
+        // Index where a side of the outline crosses a column
+        let index_at = |side: &Vec<(f64, f64)>, across: f64, fallback: f64| -> f64 {
+            for pair in side.windows(2) {
+                let ((u0, i0), (u1, i1)) = (pair[0], pair[1]);
+                if across >= u0.min(u1) - 1e-9 && across <= u0.max(u1) + 1e-9 {
+                    return if (u1 - u0).abs() < 1e-12 { i0.max(i1) } else { i0 + (i1 - i0) * (across - u0) / (u1 - u0) };
+                }
+            }
+            fallback
+        };
+
+        let top: Vec<(f64, f64)> = top.iter().map(to_column_coords).collect();
+        let bottom: Vec<(f64, f64)> = bottom.iter().map(to_column_coords).collect();
+
+        (0..=num_columns).map(|column| {
+            let across = column as f64 / num_columns.max(1) as f64;
+            let max = (index_at(&top, across, last_idx as f64).round() as usize).min(last_idx);
+            let min = (index_at(&bottom, across, 0.0).round() as usize).min(max);
+            (min, max)
+        }).collect()
+    }
+
+    // Pattern pieces for one gore, bottom to top. Cuts split polygonal gores into several pieces
+    fn to_pattern_pieces(&self, resolution: u32) -> Vec<PatternPiece> {
+        let piece = self.to_pattern_piece(resolution);
+
+        if self.cuts.is_empty() || !matches!(self.section_type, ChuteSectionType::Polygonal(_)) {
+            return vec![piece];
+        }
+
+        let (min, max) = geometry::Points::from_vec(piece.segments.iter().flat_map(|seg| seg.points.iter().cloned()).collect()).bounds();
+
+        let mut cuts = self.cuts.clone();
+        cuts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut pieces = vec![piece];
+
+        for (ratio, angle) in cuts {
+            let origin = vec2((min.x + max.x) * 0.5, min.y + ratio.clamp(0.0, 1.0) * (max.y - min.y));
+            let angle = angle.clamp(-MAX_SLANT_ANGLE, MAX_SLANT_ANGLE);
+
+            // Piece below the cut gets a new top edge, piece above gets a new bottom edge
+            pieces = pieces.iter()
+                .flat_map(|piece| {
+                    let (below, above) = piece.split(origin, angle, self.seam_allowance.1, self.seam_allowance.3);
+                    [below, above]
+                })
+                .filter(|piece| piece.get_outline_area().abs() > 1e-9)
+                .collect();
+        }
+
+        pieces
+    }
+
+    fn to_pattern_piece(&self, resolution: u32) -> PatternPiece {
+        match &self.section_type {
+            ChuteSectionType::Circular(circ) => {
+                // Generate flat piece that can be folded into cone section/cylinder/disk
+                let mut piece = PatternPiece::new();
+                piece.set_corner_cutout(self.corner_cutout);
+                let pt1 = circ.line.begin;
+                let pt2 = circ.line.end;
+
+                let (pt1, pt2, allowance, fullness) = if circ.line.end.x >= circ.line.begin.x {
+                    (circ.line.begin, circ.line.end, self.seam_allowance, self.fullness)
+                } else {
+                    // Rotate seam allowance 180 deg
+                    (circ.line.end, circ.line.begin, (self.seam_allowance.2, self.seam_allowance.3, self.seam_allowance.0, self.seam_allowance.1), (self.fullness.1, self.fullness.0))
+                };
+                // PT2 is "outer" one. Fullness is given for (inner, outer) edge
+
+                let distance = (pt1 - pt2).norm(); // band size of the half circle
+                if distance < 1e-6 || self.gores == 0 {
+                    // Empty pattern piece
+                    return piece;
+                } else if (pt2.x - pt1.x).abs() < 1e-6 {
+                    // Vertical band. Just generate a rectangle. Bottom of rectangle = towards parachute skirt
+                    // Center horizontally.
+                    // Start vertically at y=0
+                    let pos_x = pt1.x * 2.0 * PI/(self.gores as f64)/2.0; // x coord
+                    let pos_y = (pt1.y - pt2.y).abs(); // y coord
+
+                    // With fullness, the rectangle becomes a trapezoid
+                    let (fullness_bottom, fullness_top) = if pt1.y <= pt2.y { fullness } else { (fullness.1, fullness.0) };
+
+                    let bottom_left = vec2(-pos_x * (1.0 + fullness_bottom), 0.0);
+                    let bottom_right = vec2(pos_x * (1.0 + fullness_bottom), 0.0);
+                    let top_left = vec2(-pos_x * (1.0 + fullness_top), pos_y);
+                    let top_right = vec2(pos_x * (1.0 + fullness_top), pos_y);
+
+                    // Define segments clockwise to make seams work correctly
+
+                    // Right segment
+                    piece.add_segment(Segment::from_vec(vec![bottom_right, top_right], allowance.0));
+                    // Top segment
+                    piece.add_segment(Segment::from_vec(vec![top_right, top_left], allowance.1));
+                    // Left segment
+                    piece.add_segment(Segment::from_vec(vec![top_left, bottom_left], allowance.2));
+                    // Bottom segment
+                    piece.add_segment(Segment::from_vec(vec![bottom_left, bottom_right], allowance.3));
+
+                    return piece;                    
+                }
+
+                
+                let y_intersect = -pt1.x * ((pt2.y - pt1.y)/(pt2.x - pt1.x));
+                let inner_radius = (pt1.x.powi(2) + y_intersect.powi(2)).sqrt();
+                let outer_radius = distance + inner_radius;
+
+                let angle = (pt2.x)/(outer_radius) * 2.0 * PI / self.gores as f64;
+
+                // Widen the piece around its center line, interpolating fullness linearly between the inner and outer radius.
+                // Apex of the cone is at (0, outer_radius)
+                let apply_fullness = |piece: &mut PatternPiece| {
+                    for seg in piece.segments.iter_mut() {
+                        for pt in seg.points.iter_mut() {
+                            let radius = (*pt - vec2(0.0, outer_radius)).norm();
+                            pt.x *= 1.0 + fullness.0 + (fullness.1 - fullness.0) * (radius - inner_radius) / distance;
+                        }
+                    }
+                };
+
+
+                // Different cases. If top seam allowance is greater than top radius, or inner_radius is zero:
+                if angle >= 1.999 * PI && inner_radius <= allowance.1 {
+                    // Make full circle, no inner cutout. A flat disk can't be widened, so fullness is ignored
+
+                    let num_steps = resolution as usize;
+
+                    let mut outer_points = vec![];
+            
+                    for idx in 0..=num_steps {
+                        let angle = (idx as f64 / num_steps as f64) * 2.0 * PI;
+                        outer_points.push(vec2(angle.cos() * outer_radius, angle.sin()*outer_radius));
+                    }
+
+                    piece.add_segment(Segment::from_vec(outer_points, allowance.3)); // Bottom seam allowance
+                    return piece;
+            
+                } else if angle >= 1.999 * PI {
+                    // Make full circle, with inner cutout
+
+                    let num_steps = resolution as usize;
+
+                    let mut outer_points = vec![];
+            
+                    for idx in 0..=num_steps {
+                        let angle = (idx as f64 / num_steps as f64) * 2.0 * PI;
+                        outer_points.push(vec2(angle.cos() * outer_radius, angle.sin()*outer_radius));
+                    }
+
+                    piece.add_segment(Segment::from_vec(outer_points, allowance.3)); // Bottom seam allowance
+
+                    // Inner cutout, with the seam allowance going into the hole
+                    let mut inner_points = vec![];
+
+                    for idx in (0..=num_steps).rev() {
+                        let angle = (idx as f64 / num_steps as f64) * 2.0 * PI;
+                        inner_points.push(vec2(angle.cos() * inner_radius, angle.sin()*inner_radius));
+                    }
+
+                    let mut hole = PatternPiece::new();
+                    hole.set_corner_cutout(false);
+                    hole.add_segment(Segment::from_vec(inner_points, allowance.1)); // Top seam allowance
+                    piece.add_hole(hole);
+                    
+                    return piece;
+
+                } else if inner_radius <= allowance.1 {
+                    // fraction of circle, no inner cutout
+                    // Bottom at x axis
+                    let start = -angle/2.0 - PI / 2.0;
+                    let stop = start + angle;
+                    let diff = stop - start;
+                    let num_steps = ((diff / (2.0 * PI) * (resolution as f64)) as usize).max(2);
+            
+                    let mut outer_points = vec![];
+                    for idx in 0..=num_steps {
+                        let angle = start + (idx as f64 / num_steps as f64) * diff;
+                        let cos_sin = (angle.cos(), angle.sin());
+                        outer_points.push(vec2(cos_sin.0 * outer_radius, cos_sin.1 * outer_radius + outer_radius));
+                    }
+
+                    // Outer segment
+                    piece.add_segment(Segment::from_vec(outer_points, allowance.3));
+
+
+                    let start_outer = vec2(start.cos() * outer_radius, start.sin() * outer_radius + outer_radius);
+                    let end_outer = vec2(stop.cos() * outer_radius, stop.sin() * outer_radius + outer_radius);
+                    let inner = vec2(0.0, 0.0 + outer_radius);
+                    // Connecting point, right side
+                    piece.add_segment(Segment::from_vec(vec![end_outer, inner], allowance.0));
+
+                    // Connecting piece, left side
+                    piece.add_segment(Segment::from_vec(vec![inner, start_outer], allowance.2));
+
+                    apply_fullness(&mut piece);
+
+                    return piece;
+                } else {
+                    // Make fraction of a circle, with inner cutout
+                    let start = -angle/2.0 - PI / 2.0;
+                    let stop = start + angle;
+                    let diff = stop - start;
+                    let num_steps = ((diff / (2.0 * PI) * (resolution as f64)) as usize).max(2);
+            
+                    let mut outer_points = vec![];
+                    let mut inner_points = vec![];
+            
+                    for idx in 0..=num_steps {
+                        let angle = start + (idx as f64 / num_steps as f64) * diff;
+                        let cos_sin = (angle.cos(), angle.sin());
+                        outer_points.push(vec2(cos_sin.0 * outer_radius, cos_sin.1 * outer_radius + outer_radius));
+                        inner_points.push(vec2(cos_sin.0 * inner_radius, cos_sin.1 * inner_radius + outer_radius))
+                    }
+
+                    inner_points.reverse(); // Reverse inner to keep clockwise ordering
+                    // Outer segment
+                    piece.add_segment(Segment::from_vec(outer_points, allowance.3));
+
+
+                    let start_outer = vec2(start.cos() * outer_radius, start.sin() * outer_radius + outer_radius);
+                    let end_outer = vec2(stop.cos() * outer_radius, stop.sin() * outer_radius + outer_radius);
+                    let start_inner = vec2(stop.cos() * inner_radius, stop.sin() * inner_radius + outer_radius);
+                    let end_inner = vec2(start.cos() * inner_radius, start.sin() * inner_radius + outer_radius);
+
+                    // Connecting point, right side
+                    piece.add_segment(Segment::from_vec(vec![end_outer, start_inner], allowance.0));
+
+                    // Inner curve, top side
+                    piece.add_segment(Segment::from_vec(inner_points, allowance.1));
+
+                    // Connecting piece, left side
+                    piece.add_segment(Segment::from_vec(vec![end_inner, start_outer], allowance.2));
+
+                    apply_fullness(&mut piece);
+
+                    return piece;
+                }
+            },
+            ChuteSectionType::Polygonal(poly) => {
+                // To polygonal gore pattern
+                let mut piece = PatternPiece::new();
+
+                let Some([right_pts, top_pts, left_pts, bottom_pts]) = self.get_gore_outline(resolution) else {
+                    return piece;
+                };
+
+                piece.set_corner_cutout(self.corner_cutout);
+
+                piece.add_segment(Segment::from_vec(right_pts, self.seam_allowance.0));
+                piece.add_segment(Segment::from_vec(top_pts, self.seam_allowance.1));
+                piece.add_segment(Segment::from_vec(left_pts, self.seam_allowance.2));
+                piece.add_segment(Segment::from_vec(bottom_pts, self.seam_allowance.3));
+
+                return piece;
+            }
+        }
+
+        PatternPiece::new()
+    }
+
+}
+
+impl geometry::ToPoints for ChuteSection {
+    fn to_points(&self, resolution: u32) -> geometry::Points {
+        self.get_cross_section(resolution, false)
+    }
+}
+
+// Standard unit combinations for input sliders
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize)]
+pub enum StandardUnit {
+    #[default] UnitLess, // Can be used if other SI units are needed
+    MeterFoot,
+    MillimeterInch,
+    CentimeterInch,
+    Radian,
+    Degree,
+}
+
+impl StandardUnit {
+    pub fn get_options() -> Vec<Self> {
+        vec![
+            Self::UnitLess,
+            Self::MeterFoot,
+            Self::MillimeterInch,
+            Self::CentimeterInch,
+            Self::Radian,
+            Self::Degree,
+        ]
+    }
+
+    pub fn get_general_name(&self) -> String {
+        match self {
+            Self::UnitLess => "unitless".into(),
+            Self::MeterFoot => "m | ft".into(),
+            Self::MillimeterInch => "mm | in".into(),
+            Self::CentimeterInch => "cm | in".into(),
+            Self::Radian => "rad".into(),
+            Self::Degree => "deg".into(),
+        }
+    }
+
+    // Metric text for a value given in SI base unit
+    pub fn format_value(&self, value: f64) -> String {
+        match self {
+            Self::UnitLess => format!("{:.4}", value),
+            Self::MeterFoot => format!("{:.4} m", value),
+            Self::MillimeterInch => format!("{:.1} mm", value * 1000.0),
+            Self::CentimeterInch => format!("{:.2} cm", value * 100.0),
+            Self::Radian => format!("{:.4} rad", value),
+            Self::Degree => format!("{:.2} deg", value.to_degrees()),
+        }
+    }
+}
+
+// Represents a slider that can be used as input
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct InputValue {
+    pub id: String, // Unique identifier
+    pub description: String, // Short description of what it does
+    pub value: f64,
+    pub unit: StandardUnit,
+    pub range: std::ops::RangeInclusive<f64>, // Range, given in SI base unit
+    pub default_value: f64,
+}
+
+// These are parameters that are computed using the InputValues. 
+// They are evaluated sequentially and may refer to previous computed ParameterValue variables.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct ParameterValue {
+    pub id: String,
+    pub expression: String, // Mathematical expression. Value not needed since it's saved in the context
+    pub display_unit: StandardUnit, // Only for display purposes. 
+}
+
+
+// Parachute designer interface, implements the relevant UI drawing functions
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ChuteDesigner {
+    format_version: u64, // Version of the .chute file format, see file_format.rs
+    name: String,
+    gores: u16,
+    diameter: f64,
+
+    fabric: FabricSelector,
+    instructions: Vec<String>,
+    use_global_seam_allowance: bool,
+    global_seam_allowance: f64,
+    fabric_width: Option<f64>, // Exports nest all pieces onto a fabric roll of this width (m). Otherwise pieces are placed in a row
+    suspension_lines: SuspensionLines,
+    tape_linear_density: f64, // Reinforcement tape sewn along the seams (g/m), 0 if none
+    pack_density: f64, // Density of the packed canopy and lines (kg/m3)
+    reefing: Option<CanopyReefing>,
+    simulation: sim::SimSettings,
+    measurements: Vec<DescentMeasurement>, // Drop tests, see the Experiment tab
+
+    input_values: Vec<InputValue>, // Each needs a name, value, range (in m or deg).
+    parameter_values: Vec<ParameterValue>, // always in SI units
+
+    chute_sections: Vec<ChuteSection>,
+
+    #[serde(skip)]
+    #[serde(default = "ChuteDesigner::default_context")]
+    evaluator_context: evalexpr::HashMapContext, // evaluator that handles variables etc. Note: stored value always in SI base unit
+
+    #[serde(skip)]
+    dispersion: Option<(sim::SimSettings, f64, sim::Dispersion)>, // Last Monte Carlo run with the settings and canopy area it used
+
+    #[serde(skip)]
+    pattern_summary: Option<PatternSummary>, // Updated with update_pattern_summary when the design changes
+}
+
+
+impl PartialEq for ChuteDesigner {
+    fn eq(&self, other: &Self) -> bool {
+        if self.name.ne(&other.name) { false }
+        else if self.gores.ne(&other.gores) { false }
+        else if self.diameter.ne(&other.diameter) { false }
+        else if self.fabric.ne(&other.fabric) { false }
+        else if self.instructions.ne(&other.instructions) { false }
+        else if self.use_global_seam_allowance.ne(&other.use_global_seam_allowance) { false }
+        else if self.fabric_width.ne(&other.fabric_width) { false }
+        else if self.suspension_lines.ne(&other.suspension_lines) { false }
+        else if self.tape_linear_density.ne(&other.tape_linear_density) { false }
+        else if self.pack_density.ne(&other.pack_density) { false }
+        else if self.reefing.ne(&other.reefing) { false }
+        else if self.simulation.ne(&other.simulation) { false }
+        else if self.measurements.ne(&other.measurements) { false }
+        else if self.input_values.ne(&other.input_values) { false }
+        else if self.parameter_values.ne(&other.parameter_values) { false }
+        else if self.chute_sections.ne(&other.chute_sections) { false }
+        else { true }
+    }
+
+    fn ne(&self, other: &Self) -> bool {
+        !self.eq(other)
+    }
+}
+
+
+unit! {
+    system: uom::si;
+    quantity: uom::si::length;
+
+    @unitless: 1.0; "-", "-", "-";
+}
+
+
+impl ChuteDesigner {
+
+    pub fn from_json(s: &str) -> Result<Self, OpenChuteError> {
+        // Upgrade older files before deserializing
+        let design = file_format::migrate(serde_json::from_str(s)?)?;
+        Ok(serde_json::from_value(design)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn load_file(path: &Path) -> Result<Self, OpenChuteError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save_file(&self, path: &Path) -> Result<(), OpenChuteError> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    // Override an input value by its ID. Value is given in SI base unit. Returns false if no input has that ID
+    pub fn set_input_value(&mut self, id: &str, value: f64) -> bool {
+        if let Some(input_value) = self.input_values.iter_mut().find(|input| input.id == id) {
+            input_value.value = value;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn get_input_ids(&self) -> Vec<String> {
+        self.input_values.iter().map(|input| input.id.clone()).collect()
+    }
+
+    fn has_id_error(id: &String) -> Option<String> {
+        if id.contains(char::is_whitespace) {
+            return Some("Error: ID cannot contain whitespace characters".into());
+        }
+        else if id.len() == 0 {
+            return Some("Error: ID cannot be empty".into());
+        }
+        else if !id.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Some("Error: ID must be alphanumeric".into());
+        }
+        else if !id.chars().next().is_some_and(char::is_alphabetic) {
+            return Some("Error: First letter must be alphabetic".into());
+        }
+        else {
+            return None;
+        };
+    }
+
+    pub fn default_vars() -> Vec<(String, f64)> {
+        vec![
+            ("m".into(), 1.0),
+            ("mm".into(), 0.001),
+            ("yd".into(), 0.9144),
+            ("ft".into(), 0.3048),
+            ("inch".into(), 0.0254),
+            ("rad".into(), 1.0),
+            ("pi".into(), PI),
+            ("e".into(), core::f64::consts::E),
+            ("deg".into(), PI / 180.0)]
+    }
+
+    pub fn update_calculations(&mut self) {
+        // Call this in order to update the calculation context, only when not rendering geometry_ui
+
+        self.evaluator_context.clear_variables();
+        for (name, val) in ChuteDesigner::default_vars() {
+            self.evaluator_context.set_value(name, evalexpr::Value::Float(val)).unwrap();
+        }
+
+        for (idx, input_value) in self.input_values.iter_mut().enumerate() {
+            
+            if !evalexpr::Context::get_value(&self.evaluator_context, &input_value.id).is_some()
+                && ChuteDesigner::has_id_error(&input_value.id).is_none() {
+                self.evaluator_context.set_value(input_value.id.clone(), evalexpr::Value::Float(input_value.value)).unwrap_or_default(); 
+            }
+        }
+
+        for (idx, parameter) in self.parameter_values.iter_mut().enumerate() {
+    
+            if !evalexpr::Context::get_value(&self.evaluator_context, &parameter.id).is_some()
+                    && ChuteDesigner::has_id_error(&parameter.id).is_none() {
+
+                let computed = evalexpr::eval_number_with_context(&parameter.expression, &self.evaluator_context);
+                if computed.is_ok() {
+                    let value = computed.unwrap_or_default();
+
+                    self.evaluator_context.set_value(parameter.id.clone(), evalexpr::Value::Float(value));
+                }
+            }
+        }
+
+
+        for (idx, chute_section) in self.chute_sections.iter_mut().enumerate() {
+            chute_section.update_from_context(&self.evaluator_context);
+        }
+
+    }
+
+    pub fn get_cross_section(&self) -> Vec<geometry::Points> {
+        // get 2D cross section for display purposes
+        let mut result = vec![];
+
+        for chute_section in &self.chute_sections {
+            // use relatively low resolution at 30 points
+            result.push(chute_section.get_cross_section(30, false));
+        }
+
+        result
+    }
+
+    pub fn get_gores(&self) -> (Vec<(usize, geometry::Points)>, f64, f64) {
+        // Returns points for plotting together with the section index, area (m2) with and without seam allowance
+        
+        let mut result = vec![];
+
+        let mut area_no_seam = 0.0;
+        let mut area_with_seams = 0.0;
+
+        for (section_idx, chute_section) in self.chute_sections.iter().enumerate() {
+            for mut piece in chute_section.to_pattern_pieces(80) {
+                piece.compute();
+                area_no_seam += piece.get_area(false) * chute_section.gores as f64;
+                area_with_seams += piece.get_area(true) * chute_section.gores as f64;
+                for contour in piece.get_contours(true) {
+                    result.push((section_idx, geometry::Points::from_vec(contour)));
+                }
+            }
+        }
+
+        (result, area_with_seams, area_no_seam)
+    }
+
+    // Descriptions of pattern pieces with unusable seam allowances. These can't be exported
+    pub fn get_pattern_problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        for (section_idx, chute_section) in self.chute_sections.iter().enumerate() {
+            for mut piece in chute_section.to_pattern_pieces(80) {
+                piece.compute();
+                if let Err(e) = piece.check_seam_allowance() {
+                    problems.push(format!("Section #{}: {}", section_idx + 1, e));
+                }
+            }
+        }
+
+        problems
+    }
+
+    // Summary printed with the pattern: name, then one line per section and the total area
+    fn get_title_block(&self) -> Vec<String> {
+        let mut lines = vec![self.name.clone()];
+
+        for (section_idx, chute_section) in self.chute_sections.iter().enumerate() {
+            let (right, top, left, bottom) = chute_section.seam_allowance;
+            lines.push(format!("Section #{}: {} gores, {}, seam allowance {:.0}/{:.0}/{:.0}/{:.0} mm (right/top/left/bottom)",
+                section_idx + 1, chute_section.gores, chute_section.fabric.selected_fabric.get_name_weight(false),
+                right * 1000.0, top * 1000.0, left * 1000.0, bottom * 1000.0));
+        }
+
+        let (_, area_with_seams, area) = self.get_gores();
+        lines.push(format!("Canopy area: {:.3} m2. Total fabric area (including seams): {:.3} m2", area, area_with_seams));
+        if let Ok(mass) = self.get_mass_breakdown() {
+            lines.push(format!("Mass: {:.1} g including lines. Packed volume: {:.0} cm3", mass.total() * 1000.0, mass.packed_volume * 1e6));
+        }
+
+        lines
+    }
+
+    // Instructions, design values and cut list printed after the pattern
+    fn get_build_sheet(&self, layout: &PatternLayout) -> Vec<(String, Vec<String>)> {
+        let instructions = self.instructions.iter().enumerate().map(|(idx, step)| format!("{}. {}", idx + 1, step)).collect();
+
+        let inputs = self.input_values.iter()
+            .map(|input| format!("{} = {}  {}", input.id, input.unit.format_value(input.value), input.description))
+            .collect();
+
+        let parameters = self.parameter_values.iter().map(|parameter| {
+            let value = evalexpr::Context::get_value(&self.evaluator_context, &parameter.id).and_then(|value| value.as_number().ok());
+            match value {
+                Some(value) => format!("{} = {}  ({})", parameter.id, parameter.display_unit.format_value(value), parameter.expression),
+                None => format!("{} = invalid  ({})", parameter.id, parameter.expression),
+            }
+        }).collect();
+
+        let mut cut_list = vec![];
+        for (section_idx, chute_section) in self.chute_sections.iter().enumerate() {
+            cut_list.push(format!("Section #{}: {}", section_idx + 1, chute_section.fabric.selected_fabric.get_name_weight(false)));
+
+            // Nested layouts contain every copy separately
+            let mut labels: Vec<&str> = vec![];
+            for placed in layout.pieces.iter().filter(|placed| placed.section == section_idx) {
+                if labels.contains(&placed.label.as_str()) {
+                    continue;
+                }
+                labels.push(&placed.label);
+
+                let count: u16 = layout.pieces.iter().filter(|other| other.label == placed.label).map(|other| other.count).sum();
+                let size = placed.max - placed.min;
+                cut_list.push(format!("    {}: cut {}, {:.0} x {:.0} mm, {:.4} m2 each", placed.label, count, size.x * 1000.0, size.y * 1000.0, placed.area));
+            }
+        }
+
+        if let Some(fabric_width) = self.fabric_width {
+            cut_list.push(format!("Nested on {:.0} mm wide fabric: {:.2} m roll length, {:.0}% utilization", fabric_width * 1000.0, layout.width, layout.utilization() * 100.0));
+        }
+
+        if let Some(reefing_line_length) = self.get_reefing_line_length() {
+            cut_list.push(format!("Reefing line: {:.0} mm loop, {:.0}% of the {:.0} mm skirt", reefing_line_length * 1000.0, reefing_line_length / self.get_skirt_circumference() * 100.0, self.get_skirt_circumference() * 1000.0));
+        }
+
+        let mut materials = vec![];
+        if let Ok(bom) = self.get_bill_of_materials() {
+            for entry in &bom.entries {
+                let color = entry.color.map_or("".into(), |[r, g, b]| format!(", color #{:02x}{:02x}{:02x}", (r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8));
+                materials.push(format!("{}{}: {} pieces, {}{:.2} m, cost {:.2}", entry.fabric, color, entry.pieces, if entry.nested { "" } else { "about " }, entry.length, entry.cost));
+            }
+            materials.push(format!("Total cost: {:.2}. Canopy mass: {:.1} g", bom.total_cost(), bom.mass * 1000.0));
+        }
+
+        let mut mass_lines = vec![];
+        if let Ok(mass) = self.get_mass_breakdown() {
+            for (idx, section) in mass.sections.iter().enumerate() {
+                mass_lines.push(format!("Section #{}: fabric {:.1} g, seam tape {:.1} g", idx + 1, section.fabric * 1000.0, section.tape * 1000.0));
+            }
+            let lines = self.get_line_sizing();
+            mass_lines.push(format!("Suspension lines: {} x {:.2} m {}, {:.1} g", lines.count, lines.length, lines.cord, mass.lines * 1000.0));
+            mass_lines.push(format!("Line strength needed: {:.0} N each for {:.0} N peak load and safety factor {:.1}", lines.required_strength, self.suspension_lines.peak_load, self.suspension_lines.safety_factor));
+            mass_lines.push(format!("Total: {:.1} g. Packed volume: {:.0} cm3 at {:.0} kg/m3", mass.total() * 1000.0, mass.packed_volume * 1e6, self.pack_density));
+        }
+
+        vec![
+            ("Instructions".into(), instructions),
+            ("Input values".into(), inputs),
+            ("Computed parameters".into(), parameters),
+            ("Cut list".into(), cut_list),
+            ("Bill of materials".into(), materials),
+            ("Mass".into(), mass_lines),
+        ]
+    }
+
+    // Computes all pattern pieces and places them next to each other, starting at the bottom left. Coordinates in m
+    fn layout_pattern(&self, resolution: u32, padding: f64) -> Result<PatternLayout, OpenChuteError> {
+        let mut layout = PatternLayout { pieces: vec![], width: padding, height: 0.0 };
+
+        for (section_idx, chute_section) in self.chute_sections.iter().enumerate() {
+            let pieces = chute_section.to_pattern_pieces(resolution);
+            let num_pieces = pieces.len();
+
+            for (piece_idx, mut piece) in pieces.into_iter().enumerate() {
+                piece.compute();
+                piece.check_seam_allowance().map_err(|e| OpenChuteError::InvalidPattern(format!("Section #{}: {}", section_idx + 1, e)))?;
+
+                if piece.computed_points.is_empty() {
+                    continue;
+                }
+
+                let (min, max) = geometry::Points::from_vec(piece.computed_points.clone()).bounds();
+                let offset = vec2(layout.width - min.x, padding - min.y);
+                let place = |contours: Vec<Vec<Vector2<f64>>>| -> Vec<Vec<Vector2<f64>>> {
+                    contours.into_iter().map(|contour| contour.into_iter().map(|pt| pt + offset).collect()).collect()
+                };
+
+                let label = if num_pieces > 1 { format!("#{}.{}", section_idx + 1, piece_idx + 1) } else { format!("#{}", section_idx + 1) };
+
+                layout.pieces.push(PlacedPiece {
+                    label,
+                    section: section_idx,
+                    count: chute_section.gores,
+                    area: piece.get_area(true),
+                    cut: place(piece.get_contours(true)),
+                    stitch: place(piece.get_contours(false)),
+                    min: min + offset,
+                    max: max + offset,
+                });
+
+                layout.width += max.x - min.x + padding;
+                layout.height = layout.height.max(max.y - min.y + 2.0 * padding);
+            }
+        }
+
+        Ok(layout)
+    }
+
+    // Layout used by the exporters: nested on the fabric roll if a fabric width is set
+    fn export_layout(&self, resolution: u32, padding: f64) -> Result<PatternLayout, OpenChuteError> {
+        let layout = self.layout_pattern(resolution, padding)?;
+        match self.fabric_width {
+            Some(fabric_width) => crate::nesting::nest(&layout.pieces, fabric_width, padding),
+            None => Ok(layout),
+        }
+    }
+
+    pub fn get_fabric_width(&self) -> Option<f64> {
+        self.fabric_width
+    }
+
+    pub fn set_fabric_width(&mut self, fabric_width: Option<f64>) {
+        self.fabric_width = fabric_width;
+    }
+
+    // Nesting, bill of materials and mass for the editor. These lay out and nest all pieces, so the editor only
+    // recomputes them when the design changes, like the 3D mesh
+    pub fn update_pattern_summary(&mut self) {
+        let summary = match self.layout_pattern(80, 0.01) {
+            Ok(layout) => PatternSummary {
+                nesting: self.nesting_summary(&layout).map_err(|e| e.to_string()),
+                bill_of_materials: Ok(self.bill_of_materials(&layout)),
+                mass: Ok(self.mass_breakdown(&layout)),
+            },
+            Err(e) => PatternSummary { nesting: Err(e.to_string()), bill_of_materials: Err(e.to_string()), mass: Err(e.to_string()) },
+        };
+        self.pattern_summary = Some(summary);
+    }
+
+    // Required fabric roll length (m) and fraction of the fabric used. None if no fabric width is set
+    pub fn get_nesting_summary(&self) -> Result<Option<(f64, f64)>, OpenChuteError> {
+        self.nesting_summary(&self.layout_pattern(80, 0.01)?)
+    }
+
+    fn nesting_summary(&self, layout: &PatternLayout) -> Result<Option<(f64, f64)>, OpenChuteError> {
+        let Some(fabric_width) = self.fabric_width else {
+            return Ok(None);
+        };
+        let layout = crate::nesting::nest(&layout.pieces, fabric_width, 0.01)?;
+        Ok(Some((layout.width, layout.utilization())))
+    }
+
+    // Fabric length per fabric and color. Every color is nested separately on the roll of its fabric
+    pub fn get_bill_of_materials(&self) -> Result<BillOfMaterials, OpenChuteError> {
+        Ok(self.bill_of_materials(&self.layout_pattern(80, 0.01)?))
+    }
+
+    fn bill_of_materials(&self, layout: &PatternLayout) -> BillOfMaterials {
+        let mut groups: Vec<FabricGroup> = vec![];
+        let mut mass = 0.0;
+
+        for (section_idx, chute_section) in self.chute_sections.iter().enumerate() {
+            let fabric = &chute_section.fabric.selected_fabric;
+            let section_pieces: Vec<&PlacedPiece> = layout.pieces.iter().filter(|placed| placed.section == section_idx).collect();
+            mass += section_pieces.iter().map(|placed| placed.area * placed.count as f64).sum::<f64>() * fabric.area_density_gsm / 1000.0;
+
+            // Colors repeat over the gores
+            let mut colors: Vec<(Option<[f32; 3]>, u16)> = vec![];
+            for gore in 0..chute_section.gores as usize {
+                let color = chute_section.colors.get(gore % chute_section.colors.len().max(1)).copied();
+                match colors.iter_mut().find(|(c, _)| *c == color) {
+                    Some((_, count)) => *count += 1,
+                    None => colors.push((color, 1)),
+                }
+            }
+
+            for (color, count) in colors {
+                let idx = match groups.iter().position(|group| group.fabric == fabric && group.color == color) {
+                    Some(idx) => idx,
+                    None => {
+                        groups.push(FabricGroup { fabric, color, pieces: vec![] });
+                        groups.len() - 1
+                    }
+                };
+                groups[idx].pieces.extend(section_pieces.iter().map(|placed| PlacedPiece { count, ..(*placed).clone() }));
+            }
+        }
+
+        let entries = groups.into_iter().map(|FabricGroup { fabric, color, pieces }| {
+            let (length, nested) = match crate::nesting::nest(&pieces, fabric.roll_width, 0.01) {
+                Ok(nested) => (nested.width, true),
+                Err(_) => {
+                    let area: f64 = pieces.iter().map(|placed| (placed.max.x - placed.min.x) * (placed.max.y - placed.min.y) * placed.count as f64).sum();
+                    (area / fabric.roll_width, false)
+                }
+            };
+            BomEntry {
+                fabric: fabric.get_name_weight(false),
+                color,
+                pieces: pieces.iter().map(|placed| placed.count as u32).sum(),
+                length,
+                cost: length * fabric.price_per_meter,
+                nested,
+            }
+        }).collect();
+
+        BillOfMaterials { entries, mass }
+    }
+
+    // Mass per section and of the lines, and the packed volume of everything
+    pub fn get_mass_breakdown(&self) -> Result<MassBreakdown, OpenChuteError> {
+        Ok(self.mass_breakdown(&self.layout_pattern(80, 0.01)?))
+    }
+
+    fn mass_breakdown(&self, layout: &PatternLayout) -> MassBreakdown {
+        let sections: Vec<SectionMass> = self.chute_sections.iter().enumerate().map(|(section_idx, chute_section)| {
+            let pieces = layout.pieces.iter().filter(|placed| placed.section == section_idx);
+
+            let mut fabric_area = 0.0;
+            let mut seam_length = 0.0;
+            for placed in pieces {
+                fabric_area += placed.area * placed.count as f64;
+                // Every seam joins two pieces
+                seam_length += placed.stitch.iter().map(|contour| geometry::Points::from_vec(contour.clone()).length(true)).sum::<f64>() * placed.count as f64 * 0.5;
+            }
+
+            SectionMass {
+                fabric: fabric_area * chute_section.fabric.selected_fabric.area_density_gsm / 1000.0,
+                tape: seam_length * self.tape_linear_density / 1000.0,
+            }
+        }).collect();
+
+        let mut breakdown = MassBreakdown { sections, lines: self.get_line_sizing().mass, packed_volume: 0.0 };
+        breakdown.packed_volume = breakdown.total() / self.pack_density;
+        breakdown
+    }
+
+    // Radius of the skirt (m) and the bottom section. The skirt is the lowest edge of the canopy,
+    // the widest one if several are equally low (e.g. flat canopies). Polygonal sections give the radius of the corners
+    fn get_skirt(&self) -> Option<(f64, &ChuteSection)> {
+        let mut skirt: Option<(Vector2<f64>, &ChuteSection)> = None;
+        for chute_section in &self.chute_sections {
+            for pt in chute_section.get_cross_section(30, true).points {
+                if skirt.is_none_or(|(lowest, _)| pt.y < lowest.y - 1e-9 || (pt.y < lowest.y + 1e-9 && pt.x > lowest.x)) {
+                    skirt = Some((pt, chute_section));
+                }
+            }
+        }
+        skirt.map(|(pt, chute_section)| (pt.x, chute_section))
+    }
+
+    // Length of the hem at the skirt (m). Polygonal sections have a straight hem across each gore
+    pub fn get_skirt_circumference(&self) -> f64 {
+        match self.get_skirt() {
+            Some((radius, chute_section)) if matches!(chute_section.section_type, ChuteSectionType::Polygonal(_)) => {
+                let gores = chute_section.gores as f64;
+                gores * 2.0 * radius * (PI / gores).sin()
+            },
+            Some((radius, _)) => 2.0 * PI * radius,
+            None => 0.0,
+        }
+    }
+
+    pub fn get_reefing_line_length(&self) -> Option<f64> {
+        self.reefing.as_ref().map(|reefing| reefing.ratio * self.get_skirt_circumference())
+    }
+
+    // Canopy area without seam allowances (m2)
+    pub fn get_canopy_area(&self) -> f64 {
+        let (_, _, area) = self.get_gores();
+        area
+    }
+
+    // Diameter of a flat circle with the same area as the canopy (m)
+    pub fn get_nominal_diameter(&self) -> f64 {
+        (4.0 * self.get_canopy_area() / PI).sqrt()
+    }
+
+    // Area of the canopy seen from below, assuming it inflates to the shape of the cross section (m2)
+    pub fn get_projected_area(&self) -> f64 {
+        let radius = self.chute_sections.iter()
+            .flat_map(|section| section.get_cross_section(30, true).points)
+            .map(|pt| pt.x)
+            .fold(0.0, f64::max);
+        PI * radius * radius
+    }
+
+    // Effective drag of each descent measurement
+    pub fn get_measured_drag(&self) -> Vec<MeasuredDrag> {
+        let nominal_area = self.get_canopy_area();
+        let projected_area = self.get_projected_area();
+        self.measurements.iter().map(|measurement| measurement.get_drag(nominal_area, projected_area)).collect()
+    }
+
+    pub fn get_line_sizing(&self) -> LineSizing {
+        let lines = &self.suspension_lines;
+        let (skirt_radius, skirt_gores) = self.get_skirt().map_or((0.0, self.gores), |(radius, chute_section)| (radius, chute_section.gores));
+        let count = lines.count.unwrap_or(skirt_gores).max(1);
+
+        let length = match lines.length {
+            LineLength::Fixed(length) => length,
+            LineLength::DiameterRatio(ratio) => ratio * self.get_nominal_diameter(),
+            LineLength::Confluence(distance) => (skirt_radius.powi(2) + distance.powi(2)).sqrt(),
+        };
+
+        // Lines pull at an angle to the riser, so each one carries more than its share of the load.
+        // Lines shorter than the skirt radius can't reach the confluence point and need infinite strength
+        let cos_angle = (length.powi(2) - skirt_radius.powi(2)).max(0.0).sqrt() / length;
+        let required_strength = lines.peak_load * lines.safety_factor / (count as f64 * cos_angle);
+
+        let total_length = count as f64 * length;
+        LineSizing {
+            count,
+            length,
+            total_length,
+            mass: total_length * lines.cord.get_linear_density() / 1000.0,
+            required_strength,
+            cord: lines.cord.get_name_strength(false),
+            cord_strength: lines.cord.get_strength(),
+        }
+    }
+
+    // Simulation settings with the reefing of the design applied to the stages using its canopy
+    pub fn get_descent_settings(&self) -> sim::SimSettings {
+        let mut settings = self.simulation.clone();
+        if let Some(reefing) = &self.reefing {
+            for stage in settings.stages.iter_mut().filter(|stage| stage.area.is_none()) {
+                stage.reefing = Some(sim::Reefing { drag_area_ratio: reefing.get_drag_area_ratio(), disreef: reefing.disreef });
+            }
+        }
+        settings
+    }
+
+    // Descent with the simulation settings of the design. Stages using the canopy of this design are reefed like the design
+    pub fn get_descent_sim(&self) -> sim::Sim {
+        sim::Sim::from_settings(&self.get_descent_settings(), self.get_canopy_area())
+    }
+
+    pub fn get_landing_dispersion(&self) -> Option<sim::Dispersion> {
+        sim::landing_dispersion(&self.get_descent_settings(), self.get_canopy_area())
+    }
+
+    pub fn export_dxf(&mut self, path: PathBuf) -> Result<(), OpenChuteError> {
+
+        // Create a new DXF drawing
+        let mut drawing = Drawing::new();
+
+        let layout = self.export_layout(360, 0.1)?; // High resolution for export, 10 cm between pieces
+
+        // Separate layers so laser cutter software can cut one layer and engrave/mark the others
+        for (name, color) in [(DXF_LAYER_CUT, 7), (DXF_LAYER_STITCH, 1), (DXF_LAYER_LABELS, 5), (DXF_LAYER_MARKS, 3)] {
+            drawing.add_layer(dxf::tables::Layer { name: name.into(), color: dxf::Color::from_index(color), ..Default::default() });
+        }
+
+        let to_dxf = |pt: &Vector2<f64>| dxf::Point::new(pt.x * 1000.0, pt.y * 1000.0, 0.0);
+
+        let add_entity = |drawing: &mut Drawing, entity_type: EntityType, layer: &str| {
+            let mut entity = Entity::new(entity_type);
+            entity.common.layer = layer.into();
+            drawing.add_entity(entity);
+        };
+
+        for placed in &layout.pieces {
+            // Outlines and cutouts are separate closed polylines
+            for (contours, layer) in [(&placed.cut, DXF_LAYER_CUT), (&placed.stitch, DXF_LAYER_STITCH)] {
+                for contour in contours {
+                    let mut polyline = Polyline::default();
+                    polyline.set_is_closed(true);
+
+                    for point in contour {
+                        polyline.add_vertex(&mut drawing, Vertex::new(to_dxf(point)));
+                    }
+
+                    add_entity(&mut drawing, EntityType::Polyline(polyline), layer);
+                }
+            }
+
+            for (start, end) in placed.grainline().into_iter().chain(placed.notches(DXF_NOTCH_LENGTH)) {
+                add_entity(&mut drawing, EntityType::Line(dxf::entities::Line::new(to_dxf(&start), to_dxf(&end))), DXF_LAYER_MARKS);
+            }
+
+            // Along the grainline, on its left side
+            let anchor = (placed.min + placed.max) * 0.5 - vec2(0.002, 0.0);
+            let mut label = Text::default();
+            label.value = format!("{} (x{})", placed.label, placed.count);
+            label.text_height = DXF_TEXT_HEIGHT;
+            label.rotation = 90.0;
+            label.horizontal_text_justification = dxf::enums::HorizontalTextJustification::Center;
+            label.location = to_dxf(&anchor);
+            label.second_alignment_point = to_dxf(&anchor);
+            add_entity(&mut drawing, EntityType::Text(label), DXF_LAYER_LABELS);
+        }
+
+        drawing.header.default_drawing_units = dxf::enums::Units::Millimeters;
+
+        let mut backup_path = path.clone();
+        backup_path.set_extension("dxf.chute");
+
+        // Backup the parachute file
+        self.save_file(&backup_path)?;
+
+        // Save the drawing to a DXF file
+        drawing.save_file(path)?;
+
+        println!("Gores saved to dxf");
+        // TODO: location select
+
+        Ok(())
+    }
+
+    // Paper sizes other than PaperSize::Full tile the pattern onto several pages with alignment marks
+    pub fn export_pdf(&mut self, path: PathBuf, paper: crate::pdf::PaperSize) -> Result<(), OpenChuteError> {
+
+        let layout = self.export_layout(360, 0.05)?; // High resolution for export
+
+        let doc = crate::pdf::write_pattern(&layout, paper, &self.get_title_block(), &self.get_build_sheet(&layout))?;
+
+        let mut backup_path = path.clone();
+        backup_path.set_extension("pdf.chute");
+
+        // Backup the parachute file
+        self.save_file(&backup_path)?;
+
+        doc.save(&mut std::io::BufWriter::new(std::fs::File::create(path)?))?;
+        Ok(())
+    }
+
+    // True scale SVG in mm, e.g. for vinyl or laser cutters
+    pub fn export_svg(&mut self, path: PathBuf) -> Result<(), OpenChuteError> {
+        let layout = self.export_layout(360, 0.05)?; // High resolution for export
+
+        let width = layout.width * 1000.0;
+        let height = layout.height * 1000.0;
+
+        // SVG y axis points down
+        let path_data = |contour: &Vec<Vector2<f64>>| -> String {
+            let mut data = String::new();
+            for (idx, pt) in contour.iter().enumerate() {
+                data += &format!("{}{:.3} {:.3} ", if idx == 0 { "M" } else { "L" }, pt.x * 1000.0, height - pt.y * 1000.0);
+            }
+            data + "Z"
+        };
+
+        let mut svg = String::new();
+        svg += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+        svg += &format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.3}mm\" height=\"{:.3}mm\" viewBox=\"0 0 {:.3} {:.3}\">\n", width, height, width, height);
+        svg += &format!("<title>{}</title>\n", xml_escape(&self.name));
+        if let Ok(mass) = self.get_mass_breakdown() {
+            svg += &format!("<desc>Mass: {:.1} g including lines. Packed volume: {:.0} cm3</desc>\n", mass.total() * 1000.0, mass.packed_volume * 1e6);
+        }
+
+        svg += "<g id=\"cut\" fill=\"none\" stroke=\"#000000\" stroke-width=\"0.5\">\n";
+        for contour in layout.pieces.iter().flat_map(|placed| placed.cut.iter()) {
+            svg += &format!("<path d=\"{}\"/>\n", path_data(contour));
+        }
+        svg += "</g>\n";
+
+        svg += "<g id=\"stitch\" fill=\"none\" stroke=\"#ff4f00\" stroke-width=\"0.3\" stroke-dasharray=\"4 2\">\n";
+        for contour in layout.pieces.iter().flat_map(|placed| placed.stitch.iter()) {
+            svg += &format!("<path d=\"{}\"/>\n", path_data(contour));
+        }
+        svg += "</g>\n";
+
+        svg += "<g id=\"labels\" font-family=\"sans-serif\" text-anchor=\"middle\" fill=\"#000000\">\n";
+        for placed in &layout.pieces {
+            let center = (placed.min + placed.max) * 0.5 * 1000.0;
+            let font_size = ((placed.max.x - placed.min.x) * 1000.0 / 5.0).clamp(3.0, 30.0);
+            svg += &format!("<text x=\"{:.3}\" y=\"{:.3}\" font-size=\"{:.1}\">{} (x{})</text>\n", center.x, height - center.y, font_size, xml_escape(&placed.label), placed.count);
+        }
+        svg += "</g>\n";
+        svg += "</svg>\n";
+
+        let mut backup_path = path.clone();
+        backup_path.set_extension("svg.chute");
+
+        // Backup the parachute file
+        self.save_file(&backup_path)?;
+
+        std::fs::write(path, svg)?;
+        Ok(())
+    }
+
+    pub fn default_context() -> evalexpr::HashMapContext {
+        evalexpr::context_map! {
+            "m" => 1.0,
+            "mm" => 0.001,
+            "yd" => 0.9144,
+            "ft" => 0.3048,
+            "in" => 0.0254,
+            "rad" => 1.0,
+            "pi" => PI,
+            "e" => core::f64::consts::E,
+            "deg" => PI / 180.0,
+            "ln" => Function::new(|arg| Ok(evalexpr::Value::Float(arg.as_float()?.ln())))
+        }.unwrap()
+    }
+}
+
+#[cfg(feature = "gui")]
+impl ChuteDesigner {
+    pub fn options_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, use_imperial: bool) {
+
+        
+        // Number of gores
+        /*
+        ui::integer_edit_field(ui, &mut self.gores);
+        
+        ui.checkbox(&mut self.use_global_seam_allowance, "Use global seam allowance");
+
+        ui::dimension_field(ui, &mut self.diameter, use_imperial, 0.0..=10.0);
+        ui::number_edit_field(ui, &mut self.diameter);
+        */
+        //ui.add_enabled(self.use_global_seam_allowance, egui::Slider::new::<f64>(&mut self.global_seam_allowance, 0.0..=5.0));
+        //ui.add_enabled(self.use_global_seam_allowance, egui::Checkbox::new(&mut false, "Cut corners of seam allowance"));
+
+        //egui::widgets::color_picker::color_edit_button_rgb(ui, &mut self.test_color);
+
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut self.name)
+        });
+
+        for (idx, input_value) in self.input_values.iter_mut().enumerate() {
+            
+            ui.horizontal(|ui| {
+                ui.label(&input_value.id);
+                if !input_value.description.is_empty() {
+                    ui.button("❓").on_hover_text_at_pointer(&input_value.description);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Value:");
+                
+                match &input_value.unit {
+                    StandardUnit::UnitLess => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &unitless, &unitless),
+                    StandardUnit::MeterFoot => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &length::meter, &length::foot),
+                    StandardUnit::MillimeterInch => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &length::millimeter, &length::inch),
+                    StandardUnit::CentimeterInch => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &length::centimeter, &length::inch),
+                    StandardUnit::Degree => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &si::angle::degree, &si::angle::degree),
+                    StandardUnit::Radian => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &si::angle::radian, &si::angle::radian),
+                };
+
+                if ui.add_enabled(input_value.value != input_value.default_value, egui::Button::new("↺")).clicked() {
+                    input_value.value = input_value.default_value;
+                }
+
+            });
+
+            ui.separator();
+        }
+
+        for (idx, chute_section) in self.chute_sections.iter_mut().enumerate() {
+            ui.separator();
+            let res = egui::Frame::none().stroke(egui::Stroke::new(1.0, egui::Color32::GRAY)).inner_margin(10.0).outer_margin(5.0).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        chute_section.simple_ui(ui, frame, use_imperial, &self.evaluator_context, idx as u16);
+                    });
+                });
+            }).response;
+        }
+
+        ui.separator();
+        self.nesting_ui(ui, use_imperial);
+
+        ui.separator();
+        self.bill_of_materials_ui(ui);
+
+        ui.separator();
+        self.suspension_lines_ui(ui, use_imperial);
+
+        ui.separator();
+        self.reefing_ui(ui, use_imperial);
+
+        ui.separator();
+        self.mass_ui(ui, use_imperial);
+    }
+
+    fn suspension_lines_ui(&mut self, ui: &mut egui::Ui, use_imperial: bool) {
+        let sizing = self.get_line_sizing();
+        let skirt_radius = self.get_skirt().map_or(0.0, |(radius, _)| radius);
+        let nominal_diameter = self.get_nominal_diameter();
+
+        ui.collapsing("Suspension lines", |ui| {
+            let mut per_gore = self.suspension_lines.count.is_none();
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut per_gore, "One line per gore").changed() {
+                    self.suspension_lines.count = if per_gore { None } else { Some(sizing.count) };
+                }
+                if let Some(count) = &mut self.suspension_lines.count {
+                    ui.label("Lines:");
+                    ui::integer_edit_field(ui, count);
+                }
+            });
+
+            // Switching keeps the current line length
+            egui::ComboBox::from_id_source("suspension_line_length")
+                .width(200.0)
+                .selected_text(self.suspension_lines.length.to_string())
+                .show_ui(ui, |ui| {
+                    let options = [
+                        LineLength::Fixed(sizing.length),
+                        LineLength::DiameterRatio(sizing.length / nominal_diameter.max(1e-6)),
+                        LineLength::Confluence((sizing.length.powi(2) - skirt_radius.powi(2)).max(0.0).sqrt()),
+                    ];
+                    for option in options {
+                        let selected = std::mem::discriminant(&self.suspension_lines.length) == std::mem::discriminant(&option);
+                        if ui.selectable_label(selected, option.to_string()).clicked() && !selected {
+                            self.suspension_lines.length = option;
+                        }
+                    }
+                }
+            );
+
+            ui.horizontal(|ui| {
+                match &mut self.suspension_lines.length {
+                    LineLength::Fixed(value) => {
+                        ui.label("Line length:");
+                        ui::length_slider(ui, value, use_imperial, 0.0..=10.0, &length::meter, &length::foot);
+                    },
+                    LineLength::DiameterRatio(ratio) => {
+                        ui.label("Line length / diameter:");
+                        ui::number_edit_field(ui, ratio);
+                    },
+                    LineLength::Confluence(distance) => {
+                        ui.label("Skirt to confluence point:");
+                        ui::length_slider(ui, distance, use_imperial, 0.0..=10.0, &length::meter, &length::foot);
+                    },
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Peak load:");
+                ui::length_slider(ui, &mut self.suspension_lines.peak_load, use_imperial, 0.0..=5000.0, &si::force::newton, &si::force::pound_force);
+                ui.label("Safety factor:");
+                ui::number_edit_field(ui, &mut self.suspension_lines.safety_factor);
+            });
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("suspension_line_cord")
+                    .width(200.0)
+                    .selected_text(self.suspension_lines.cord.get_name_strength(use_imperial))
+                    .show_ui(ui, |ui| {
+                        for cord in Cord::get_types() {
+                            let text = cord.get_name_strength(use_imperial);
+                            ui.selectable_value(&mut self.suspension_lines.cord, cord, text);
+                        }
+                    }
+                );
+
+                let lightest = Cord::lightest_with_strength(sizing.required_strength);
+                if ui.add_enabled(lightest.is_some(), egui::Button::new("Lightest sufficient cord")).clicked() {
+                    if let Some(cord) = lightest {
+                        self.suspension_lines.cord = cord;
+                    }
+                }
+            });
+
+            ui.label(format!("{} lines of {:.2} m, {:.2} m in total, {:.1} g", sizing.count, sizing.length, sizing.total_length, sizing.mass * 1000.0));
+            if !sizing.required_strength.is_finite() {
+                ui.colored_label(ui.visuals().warn_fg_color, "⚠ Lines are shorter than the skirt radius");
+            } else if sizing.is_strong_enough() {
+                ui.label(format!("Strength needed per line: {:.0} N", sizing.required_strength));
+            } else {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ Strength needed per line: {:.0} N, but the cord holds {:.0} N", sizing.required_strength, sizing.cord_strength));
+            }
+        });
+    }
+
+    fn reefing_ui(&mut self, ui: &mut egui::Ui, use_imperial: bool) {
+        let skirt_circumference = self.get_skirt_circumference();
+
+        ui.collapsing("Reefing", |ui| {
+            let mut reefed = self.reefing.is_some();
+            if ui.checkbox(&mut reefed, "Reefed skirt").changed() {
+                self.reefing = if reefed { Some(CanopyReefing::default()) } else { None };
+            }
+
+            if let Some(reefing) = &mut self.reefing {
+                ui.horizontal(|ui| {
+                    ui.label("Reefing ratio:");
+                    ui::number_edit_field(ui, &mut reefing.ratio).on_hover_text("Reefing line length / skirt circumference");
+                    reefing.ratio = reefing.ratio.clamp(0.0, 1.0);
+                });
+
+                ui.horizontal(|ui| {
+                    let mut measured = reefing.drag_area_ratio.is_some();
+                    if ui.checkbox(&mut measured, "Measured reefed / full CdS").changed() {
+                        reefing.drag_area_ratio = if measured { Some(reefing.get_drag_area_ratio()) } else { None };
+                    }
+                    let estimate = reefing.get_drag_area_ratio();
+                    match &mut reefing.drag_area_ratio {
+                        Some(ratio) => { ui::number_edit_field(ui, ratio); },
+                        None => { ui.label(format!("Estimated: {:.3}", estimate)).on_hover_text("Drag area taken to scale with the square of the skirt diameter"); },
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Disreefs:");
+                    Self::trigger_ui(ui, &mut reefing.disreef, "design_disreef".into(), use_imperial);
+                });
+
+                let line_length = reefing.ratio * skirt_circumference;
+                let (line_length, circumference) = if use_imperial { (line_length / 0.0254, skirt_circumference / 0.0254) } else { (line_length * 1000.0, skirt_circumference * 1000.0) };
+                let unit = if use_imperial { "in" } else { "mm" };
+                ui.label(format!("Reefing line: {:.0} {} loop around the {:.0} {} skirt", line_length, unit, circumference, unit));
+            }
+        });
+    }
+
+    fn mass_ui(&mut self, ui: &mut egui::Ui, use_imperial: bool) {
+        ui.collapsing("Mass and packed volume", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Seam tape [g/m]:");
+                ui::number_edit_field(ui, &mut self.tape_linear_density);
+                ui.label("Packing density [kg/m³]:");
+                ui::number_edit_field(ui, &mut self.pack_density);
+            });
+
+            match self.pattern_summary.as_ref().map(|summary| &summary.mass) {
+                Some(Ok(mass)) => {
+                    for (idx, section) in mass.sections.iter().enumerate() {
+                        ui.label(format!("Section #{}: fabric {:.1} g, seam tape {:.1} g", idx + 1, section.fabric * 1000.0, section.tape * 1000.0));
+                    }
+                    ui.label(format!("Suspension lines: {:.1} g", mass.lines * 1000.0));
+                    ui.label(format!("Total mass: {:.1} g. Packed volume: {:.0} cm³", mass.total() * 1000.0, mass.packed_volume * 1e6));
+                },
+                Some(Err(e)) => { ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", e)); },
+                None => {},
+            }
+        });
+    }
+
+    fn bill_of_materials_ui(&mut self, ui: &mut egui::Ui) {
+        let bom = match self.pattern_summary.as_ref().map(|summary| &summary.bill_of_materials) {
+            Some(Ok(bom)) => bom,
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", e));
+                return;
+            },
+            None => return,
+        };
+
+        ui.collapsing("Bill of materials", |ui| {
+            egui::Grid::new("bill_of_materials").striped(true).show(ui, |ui| {
+                ui.label("Fabric");
+                ui.label("Color");
+                ui.label("Pieces");
+                ui.label("Length");
+                ui.label("Cost");
+                ui.end_row();
+
+                for entry in &bom.entries {
+                    ui.label(&entry.fabric);
+                    match entry.color {
+                        Some([r, g, b]) => ui.label(egui::RichText::new("■■").color(egui::Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8))),
+                        None => ui.label("-"),
+                    };
+                    ui.label(format!("{}", entry.pieces));
+                    if entry.nested {
+                        ui.label(format!("{:.2} m", entry.length));
+                    } else {
+                        ui.label(format!("~{:.2} m", entry.length)).on_hover_text("Pieces are wider than the roll, estimated from their bounding boxes");
+                    }
+                    ui.label(format!("{:.2}", entry.cost));
+                    ui.end_row();
+                }
+            });
+
+            for (fabric, length, cost) in bom.fabric_totals() {
+                ui.label(format!("{}: {:.2} m, cost {:.2}", fabric, length, cost));
+            }
+            ui.label(format!("Total cost: {:.2}. Canopy mass: {:.1} g", bom.total_cost(), bom.mass * 1000.0));
+        });
+    }
+
+    fn nesting_ui(&mut self, ui: &mut egui::Ui, use_imperial: bool) {
+        let mut nest = self.fabric_width.is_some();
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut nest, "Nest pieces on fabric roll").changed() {
+                self.fabric_width = if nest { Some(DEFAULT_FABRIC_WIDTH) } else { None };
+            }
+            if let Some(fabric_width) = &mut self.fabric_width {
+                ui.label("Fabric width:");
+                ui::length_slider(ui, fabric_width, use_imperial, 0.5..=3.0, &length::meter, &length::foot);
+            }
+        });
+
+        match self.pattern_summary.as_ref().map(|summary| &summary.nesting) {
+            Some(Ok(Some((roll_length, utilization)))) => { ui.label(format!("Roll length: {:.2} m, fabric utilization {:.0}%", roll_length, utilization * 100.0)); },
+            Some(Err(e)) => { ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", e)); },
+            _ => {},
+        }
+    }
+
+    pub fn instructions_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
+        ui.label(egui::RichText::new("Instructions").font(egui::FontId::proportional(20.0)));
+        if ui.button("Add step").clicked() {
+            self.instructions.push("Step".to_owned());
+        }
+
+        let mut to_delete: Option<usize> = None;
+
+        for (num, step) in self.instructions.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.button("❌").clicked() {
+                    to_delete = Some(num);
+                }
+                ui.label(format!("{}", num+1));
+                ui.separator();
+
+                ui.add(egui::TextEdit::multiline(step).desired_rows(1));
+            });
+        }
+
+        if let Some(delete_idx) = to_delete {
+            self.instructions.remove(delete_idx);
+        }
+    }
+
+    pub fn draw_cross_section(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, highlighted: Option<u16>) {
+        let mut lines: Vec<(usize, geometry::Points)> = self.get_cross_section().into_iter().enumerate().collect();
+        lines.append(&mut lines.iter().map(|(idx, p)| (*idx, p.mirror_x())).collect());
+
+        self.equal_aspect_plot(ui, frame, &lines, highlighted, "cross_section".into());
+    }
+
+    pub fn draw_gores(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, highlighted: Option<u16>) {
+        let (mut lines, area_with_seams, area) = self.get_gores();
+        self.equal_aspect_plot(ui, frame, &lines, highlighted, "gore_plot".into());
+        ui.label(format!("Chute canopy area: {:.5} m². Total area (including seams): {:.5} m²", area, area_with_seams));
+
+        for problem in self.get_pattern_problems() {
+            ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", problem));
+        }
+    }
+
+    // Lines are given together with the index of their section, used for highlighting
+    pub fn equal_aspect_plot(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, data: &[(usize, geometry::Points)], highlighted: Option<u16>, id: String) {
+        let mut lines = vec![];
+
+        for (idx,line) in data.iter() {
+            
+            let pts: egui_plot::PlotPoints = line.points.iter().map(|pt| [pt.x, pt.y]).collect();
+            let this_line = egui_plot::Line::new(pts).width(2.0).highlight(highlighted == Some(*idx as u16));
+            
+            lines.push(this_line);
+        }
+
+        egui_plot::Plot::new(id).height(300.0).data_aspect(1.0).view_aspect(1.5).auto_bounds_x().auto_bounds_y().show(ui, |plot_ui| {
+            for line in lines {
+                plot_ui.line(line);
+            }
+        });
+    }
+
+    pub fn geometry_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, use_imperial: bool) {
+        // geometry stuff
+
+        self.evaluator_context.clear_variables();
+        for (name, val) in ChuteDesigner::default_vars() {
+            self.evaluator_context.set_value(name, evalexpr::Value::Float(val)).unwrap();
+        }
+        
+
+        ui.columns(2, |columns| {
+            let mut ui = &mut columns[0];
+
+            ui.heading("Input variables:");
+            ui.separator();
+    
+            if ui.button("Add input").clicked() {
+                self.input_values.push(InputValue { description: "".into(), id: format!("input{}", self.input_values.len()+1), range: 0.0..=10.0, unit: StandardUnit::MeterFoot, value: 1.0, default_value: 1.0})
+            }
+    
+            let mut to_delete: Option<usize> = None;
+            let mut to_move: Option<(usize, bool)> = None;
+            let num_parameters = self.input_values.len();
+    
+            for (idx, input_value) in self.input_values.iter_mut().enumerate() {
+                
+                ui.horizontal(|ui| {
+                    ui::delete_move_buttons(ui, &mut to_delete, &mut to_move, idx, num_parameters);
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("ID:");
+                    ui.text_edit_singleline(&mut input_value.id);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Description");
+                    ui.text_edit_singleline(&mut input_value.description);
+                });
+    
+                egui::ComboBox::from_id_source(&input_value.id).width(200.0)
+                .selected_text(input_value.unit.get_general_name())
+                .show_ui(ui, |ui| {
+                    for (_idx, option) in StandardUnit::get_options().iter().enumerate() {
+                        ui.selectable_value(&mut input_value.unit, option.clone(), option.get_general_name());
+                    }
+                });
+    
+    
+                ui.horizontal(|ui| {
+                    ui.label("Value:");
+                    
+                    match &input_value.unit {
+                        StandardUnit::UnitLess => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &unitless, &unitless),
+                        StandardUnit::MeterFoot => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &length::meter, &length::foot),
+                        StandardUnit::MillimeterInch => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &length::millimeter, &length::inch),
+                        StandardUnit::CentimeterInch => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &length::centimeter, &length::inch),
+                        StandardUnit::Degree => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &si::angle::degree, &si::angle::degree),
+                        StandardUnit::Radian => ui::length_slider(ui, &mut input_value.value, use_imperial, input_value.range.clone(), &si::angle::radian, &si::angle::radian),
+                    }                
+                });
+    
+                // TODO: Add unit conversion selection
+                let mut start = *input_value.range.start();
+                let mut end = *input_value.range.end();
+    
+                ui.horizontal(|ui| {
+                    ui.label("Range: ");
+                    ui::number_edit_field(ui, &mut start);
+                    ui.label("to");
+                    ui::number_edit_field(ui, &mut end);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Default value: ");
+                    ui::number_edit_field(ui, &mut input_value.default_value);
+                });
+                input_value.range = start ..= end;
+    
+                if evalexpr::Context::get_value(&self.evaluator_context, &input_value.id).is_some() {
+                    ui.label("Error: Identifier already used");
+                }
+                else if let Some(msg) = ChuteDesigner::has_id_error(&input_value.id) {
+                    ui.label(msg);
+                } else {
+                    self.evaluator_context.set_value(input_value.id.clone(), evalexpr::Value::Float(input_value.value)).unwrap_or_else(|_| {
+                        ui.label("Unable to save value...");
+                    })
+                }
+                ui.separator();
+            }
+    
+            if let Some((idx, direction)) = to_move {
+                if idx > 0 && direction {
+                    // Swap upwards
+                    self.input_values.swap(idx, idx-1);
+                } else if idx < (self.input_values.len() - 1) && !direction{
+                    self.input_values.swap(idx, idx+1);
+                }
+            }
+    
+            
+            if let Some(delete_idx) = to_delete {
+                self.input_values.remove(delete_idx);
+            }
+
+
+
+            ui.heading("Computed parameters");
+            if ui.button("Add parameter").clicked() {
+                self.parameter_values.push(ParameterValue {
+                    id: format!("param{}", self.parameter_values.len()+1),
+                    expression: "1.0*m+2.0*mm".into(),
+                    display_unit: StandardUnit::MeterFoot,
+                })
+            }
+    
+            let mut to_delete: Option<usize> = None;
+            let mut to_move: Option<(usize, bool)> = None; // Option containing index and true if moving up and false if down
+    
+            ui.push_id("paramtable", |ui| {
+                egui_extras::TableBuilder::new(ui)
+                .striped(true)
+                .column(egui_extras::Column::auto())
+                .column(egui_extras::Column::auto().at_least(60.0).resizable(true))
+                .column(egui_extras::Column::auto().at_least(120.0).resizable(true))
+                .column(egui_extras::Column::remainder())
+                .header(20.0, |mut header| {
+                    header.col(|ui| {
+                        ui.label(egui::RichText::new("Edit").strong());
+                    });
+                    header.col(|ui| {
+                        ui.label(egui::RichText::new("ID").strong());
+                    });
+                    header.col(|ui| {
+                        ui.label(egui::RichText::new("Expression").strong());
+                    });
+                    header.col(|ui| {
+                        ui.label(egui::RichText::new("Result [si unit]").strong());
+                    });
+                })
+                .body(|mut body| {
+                    let num_parameters = self.parameter_values.len();
+                    for (idx, parameter) in self.parameter_values.iter_mut().enumerate() {
+    
+    
+                        body.row(20.0, |mut row| {
+                            row.col(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui::delete_move_buttons(ui, &mut to_delete, &mut to_move, idx, num_parameters);
+                                });
+                            });
+                            row.col(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut parameter.id).clip_text(false));
+                            });
+                            row.col(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut parameter.expression).clip_text(false));
+                            });
+                            row.col(|ui| {
+                                
+                                if evalexpr::Context::get_value(&self.evaluator_context, &parameter.id).is_some() {
+                                    ui.label("Error: Identifier already used");
+                                }
+                                else if let Some(msg) = ChuteDesigner::has_id_error(&parameter.id) {
+                                    ui.label(msg);
+                                }
+                                else {
+                                    let computed = evalexpr::eval_number_with_context(&parameter.expression, &self.evaluator_context);
+                                    if computed.is_ok() {
+                                        let value = computed.unwrap_or_default();
+                                        ui.label(format!("{:}", (value * 100_000_000.0).round() / 100_000_000.0));
+                    
+                                        self.evaluator_context.set_value(parameter.id.clone(), evalexpr::Value::Float(value));
+                                    } else {
+                                        ui.label(format!("Error: {:?}", computed));
+                                    }
+                                }
+                            });
+                            
+                        });
+                    }
+                });    
+            });
+    
+    
+            if let Some((idx, direction)) = to_move {
+                if idx > 0 && direction {
+                    // Swap upwards
+                    self.parameter_values.swap(idx, idx-1);
+                } else if idx < (self.parameter_values.len() - 1) && !direction{
+                    self.parameter_values.swap(idx, idx+1);
+                }
+            }
+    
+            if let Some(delete_idx) = to_delete {
+                self.parameter_values.remove(delete_idx);
+            }
+    
+    
+            ui.add(egui::Hyperlink::from_label_and_url("Info about builtin functions", "https://docs.rs/evalexpr/latest/evalexpr/#builtin-functions"));
+            
+            ui.separator();
+    
+            ui = &mut columns[1];
+            
+
+
+            ui.heading("Cross-section geometry");
+
+            ui.horizontal(|ui| {
+                if ui.button("Add polygonal geometry").clicked() {
+                    self.chute_sections.push(ChuteSection::new_polygonal());
+                }
+                
+                if ui.button("Add circular band").clicked() {
+                    self.chute_sections.push(ChuteSection::new_circular());
+                }
+            });
+    
+    
+            // Parachute section
+    
+            let mut to_delete: Option<usize> = None;
+            let mut to_move: Option<(usize, bool)> = None; // Option containing index and true if moving up and false if down
+            let num_parameters = self.chute_sections.len();
+            let mut focus_idx: Option<u16> = None;
+
+            for (idx, chute_section) in self.chute_sections.iter_mut().enumerate() {
+                ui.separator();
+                let res = egui::Frame::none().stroke(egui::Stroke::new(1.0, egui::Color32::GRAY)).inner_margin(10.0).outer_margin(5.0).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui::delete_move_buttons(ui, &mut to_delete, &mut to_move, idx, num_parameters);
+                        });
+                        ui.separator();
+                        ui.vertical(|ui| {
+                            chute_section.ui(ui, frame, use_imperial, &self.evaluator_context, idx as u16);
+                        });
+                    });
+                }).response;
+
+                if res.hovered() {
+                    focus_idx = Some(idx as u16);
+                }
+            }
+    
+            if let Some((idx, direction)) = to_move {
+                if idx > 0 && direction {
+                    // Swap upwards
+                    self.chute_sections.swap(idx, idx-1);
+                } else if idx < (self.chute_sections.len() - 1) && !direction{
+                    self.chute_sections.swap(idx, idx+1);
+                }
+            }
+    
+            if let Some(delete_idx) = to_delete {
+                self.chute_sections.remove(delete_idx);
+            }
+    
+            ui.separator();
+    
+            self.draw_cross_section(ui, frame, focus_idx);
+
+            self.draw_gores(ui, frame, focus_idx);
+
+        });
+
+    }
+
+    // Effective Cd from descent rates measured in drop tests
+    pub fn experiment_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, use_imperial: bool) {
+        ui.label(format!("Canopy area: {:.3} m², projected area: {:.3} m²", self.get_canopy_area(), self.get_projected_area()))
+            .on_hover_text("Cd₀ is based on the canopy area, Cdp on the area of the inflated shape seen from below");
+
+        let drag = self.get_measured_drag();
+        let mut to_delete = None;
+
+        egui::Grid::new("descent_measurements").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("Total mass").on_hover_text("Payload, canopy and lines");
+            ui.label("Descent rate").on_hover_text("Steady descent rate, after the canopy has opened");
+            ui.label("Altitude");
+            ui.label("Temperature").on_hover_text("Air temperature at the measurement. Otherwise the standard atmosphere is used");
+            ui.label("Density [kg/m³]");
+            ui.label("CdS [m²]");
+            ui.label("Cd₀");
+            ui.label("Cdp");
+            ui.end_row();
+
+            for (idx, (measurement, drag)) in self.measurements.iter_mut().zip(&drag).enumerate() {
+                if ui.button("❌").on_hover_text("Delete").clicked() {
+                    to_delete = Some(idx);
+                }
+                ui::length_slider(ui, &mut measurement.payload_mass, use_imperial, 0.0..=20.0, &si::mass::kilogram, &si::mass::pound);
+                ui::length_slider(ui, &mut measurement.descent_rate, use_imperial, 0.0..=20.0, &si::velocity::meter_per_second, &si::velocity::foot_per_second);
+                ui::length_slider_no_limit(ui, &mut measurement.altitude, use_imperial, 0.0..=3000.0, &length::meter, &length::foot);
+
+                ui.horizontal(|ui| {
+                    let mut measured = measurement.temperature.is_some();
+                    if ui.checkbox(&mut measured, "").changed() {
+                        measurement.temperature = if measured { Some(sim::get_atmosphere(measurement.altitude).temperature) } else { None };
+                    }
+                    if let Some(temperature) = &mut measurement.temperature {
+                        // Stored in K
+                        let mut shown = if use_imperial { (*temperature - 273.15) * 1.8 + 32.0 } else { *temperature - 273.15 };
+                        ui.add(egui::DragValue::new(&mut shown).speed(0.1).fixed_decimals(1).suffix(if use_imperial { " °F" } else { " °C" }));
+                        let celsius = if use_imperial { (shown - 32.0) / 1.8 } else { shown };
+                        *temperature = (celsius + 273.15).max(1.0);
+                    }
+                });
+
+                ui.label(format!("{:.3}", drag.density));
+                ui.label(format!("{:.3}", drag.drag_area));
+                ui.label(format!("{:.2}", drag.cd_nominal));
+                ui.label(format!("{:.2}", drag.cd_projected));
+                ui.end_row();
+            }
+        });
+
+        if let Some(idx) = to_delete {
+            self.measurements.remove(idx);
+        }
+        if ui.button("Add measurement").clicked() {
+            let measurement = self.measurements.last().cloned().unwrap_or_default();
+            self.measurements.push(measurement);
+        }
+
+        if !drag.is_empty() {
+            let count = drag.len() as f64;
+            let cd_nominal = drag.iter().map(|drag| drag.cd_nominal).sum::<f64>() / count;
+            let cd_projected = drag.iter().map(|drag| drag.cd_projected).sum::<f64>() / count;
+            ui.label(format!("Average Cd₀: {:.2}, Cdp: {:.2}", cd_nominal, cd_projected));
+
+            if ui.button("Use in simulation").on_hover_text("Sets the Cd of the simulation stages using the canopy of this design to the average Cd₀").clicked() {
+                for stage in self.simulation.stages.iter_mut().filter(|stage| stage.area.is_none()) {
+                    stage.cd = cd_nominal;
+                }
+            }
+        }
+    }
+
+    pub fn simulation_ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, use_imperial: bool) {
+        ui.horizontal(|ui| {
+            ui.label("Payload mass:");
+            ui::length_slider(ui, &mut self.simulation.payload_mass, use_imperial, 0.0..=20.0, &si::mass::kilogram, &si::mass::pound);
+            ui.label("Start altitude (e.g. apogee):");
+            ui::length_slider_no_limit(ui, &mut self.simulation.altitude, use_imperial, 0.0..=5000.0, &length::meter, &length::foot);
+            ui.label("Downward speed at the start:");
+            ui::length_slider(ui, &mut self.simulation.speed, use_imperial, 0.0..=100.0, &si::velocity::meter_per_second, &si::velocity::foot_per_second);
+        });
+        self.simulation.payload_mass = self.simulation.payload_mass.max(0.001); // Massless payloads never land
+
+        let canopy_area = self.get_canopy_area();
+        let design_reefed = self.reefing.is_some();
+        let mut to_delete = None;
+        let mut to_move = None;
+        let num_stages = self.simulation.stages.len();
+
+        for (idx, stage) in self.simulation.stages.iter_mut().enumerate() {
+            egui::Frame::none().stroke(egui::Stroke::new(1.0, egui::Color32::GRAY)).inner_margin(10.0).outer_margin(5.0).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui::delete_move_buttons(ui, &mut to_delete, &mut to_move, idx, num_stages);
+                    ui.text_edit_singleline(&mut stage.name);
+                });
+                Self::stage_ui(ui, stage, idx, canopy_area, design_reefed, use_imperial);
+            });
+        }
+
+        if let Some(delete_idx) = to_delete {
+            self.simulation.stages.remove(delete_idx);
+        }
+        if let Some((idx, direction)) = to_move {
+            if idx > 0 && direction {
+                self.simulation.stages.swap(idx, idx - 1);
+            } else if idx < (self.simulation.stages.len() - 1) && !direction {
+                self.simulation.stages.swap(idx, idx + 1);
+            }
+        }
+        if ui.button("Add stage").clicked() {
+            self.simulation.stages.push(sim::Stage::new("Main", None, 0.8, sim::Trigger::Altitude(300.0)));
+        }
+
+        let mut wind_drift = self.simulation.wind.is_some();
+        if ui.checkbox(&mut wind_drift, "Wind drift").on_hover_text("Simulates the horizontal drift in a wind profile. Otherwise the descent is vertical").changed() {
+            self.simulation.wind = if wind_drift { Some(sim::WindProfile::default()) } else { None };
+        }
+        if let Some(wind) = &mut self.simulation.wind {
+            Self::wind_ui(ui, wind, use_imperial);
+        }
+
+        let mut sim = self.get_descent_sim();
+        let results = sim.simulate();
+        let stage_results = sim.get_stage_results();
+        let landing_point = sim.get_landing_point();
+        match results.last() {
+            Some(landing) => { ui.label(format!("Descent time: {:.1} s, landing speed: {:.2} m/s", landing.time, -landing.velocity)); },
+            None => { ui.colored_label(ui.visuals().warn_fg_color, "⚠ Start altitude is below the ground"); },
+        }
+        if let (Some(_), Some(point)) = (&self.simulation.wind, landing_point) {
+            ui.label(format!("Landing point: {:.0} m east, {:.0} m north of the start ({:.0} m drift)", point.x, point.y, point.norm()));
+        }
+
+        egui::Grid::new("stage_results").striped(true).show(ui, |ui| {
+            ui.label("Stage");
+            ui.label("Deployed");
+            ui.label("Opening time");
+            ui.label("Peak force");
+            ui.label("Load factor").on_hover_text("Peak force / (dynamic pressure at deployment * CdS)");
+            ui.label("Disreefed");
+            ui.label("Disreef force").on_hover_text("Peak force after disreefing");
+            ui.end_row();
+
+            for result in &stage_results {
+                ui.label(&result.name);
+                ui.label(result.deployment_time.map_or("-".into(), |time| format!("{:.2} s", time)));
+                ui.label(result.opening_time.map_or("-".into(), |time| format!("{:.2} s", time)));
+                ui.label(format!("{:.0} N", result.peak_force));
+                ui.label(result.load_factor.map_or("-".into(), |factor| format!("{:.2}", factor)));
+                ui.label(result.disreef_time.map_or("-".into(), |time| format!("{:.2} s", time)));
+                ui.label(result.disreef_force.map_or("-".into(), |force| format!("{:.0} N", force)));
+                ui.end_row();
+            }
+        });
+
+        // Peak load on the canopy of this design
+        let design_peak = self.simulation.stages.iter().zip(&stage_results)
+            .filter(|(stage, _)| stage.area.is_none())
+            .map(|(_, result)| result.peak_force)
+            .reduce(f64::max);
+        if ui.add_enabled(design_peak.is_some(), egui::Button::new("Use for line sizing")).on_hover_text("Sets the peak load of the suspension lines to the peak force on the canopy of this design").clicked() {
+            self.suspension_lines.peak_load = design_peak.unwrap_or_default();
+        }
+
+        let plot = |ui: &mut egui::Ui, id: &str, name: &str, value: fn(&sim::SimData) -> f64| {
+            let pts: egui_plot::PlotPoints = results.iter().map(|dat| [dat.time, value(dat)]).collect();
+            egui_plot::Plot::new(id).height(250.0).legend(egui_plot::Legend::default()).show(ui, |plot_ui| {
+                plot_ui.line(egui_plot::Line::new(pts).width(2.0).name(name));
+            });
+        };
+
+        // Time in s along the horizontal axes
+        ui.columns(2, |columns| {
+            plot(&mut columns[0], "sim_altitude", "Altitude [m]", |dat| dat.altitude);
+            plot(&mut columns[1], "sim_velocity", "Velocity [m/s]", |dat| dat.velocity);
+            plot(&mut columns[0], "sim_dynamic_pressure", "Dynamic pressure [Pa]", |dat| dat.dynamic_pressure);
+            plot(&mut columns[1], "sim_force", "Drag force [N]", |dat| dat.force);
+            plot(&mut columns[0], "sim_drag_area", "Drag area CdS [m²]", |dat| dat.drag_area);
+        });
+
+        if self.simulation.wind.is_some() {
+            self.dispersion_ui(ui, &results);
+        }
+    }
+
+    // Wind layers, entered or imported from a CSV file
+    fn wind_ui(ui: &mut egui::Ui, wind: &mut sim::WindProfile, use_imperial: bool) {
+        if ui::file_import(ui, &mut wind.csv_path, &wind.import_error, "Import CSV file", "Altitude [m], speed [m/s] and direction [°] on each line", &["csv", "txt"]) {
+            wind.import_error = wind.import().err().map(|e| e.to_string());
+        }
+
+        let mut to_delete = None;
+        egui::Grid::new("wind_layers").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("Altitude");
+            ui.label("Wind speed");
+            ui.label("From direction [°]").on_hover_text("Direction the wind blows from, clockwise from north. E.g. 270 for a westerly wind");
+            ui.end_row();
+
+            for (idx, layer) in wind.layers.iter_mut().enumerate() {
+                if ui.button("❌").on_hover_text("Delete").clicked() {
+                    to_delete = Some(idx);
+                }
+                ui::length_slider_no_limit(ui, &mut layer.altitude, use_imperial, 0.0..=5000.0, &length::meter, &length::foot);
+                ui::length_slider(ui, &mut layer.speed, use_imperial, 0.0..=30.0, &si::velocity::meter_per_second, &si::velocity::foot_per_second);
+                ui::number_edit_field(ui, &mut layer.direction);
+                ui.end_row();
+            }
+        });
+
+        if let Some(idx) = to_delete {
+            wind.layers.remove(idx);
+        }
+        if ui.button("Add layer").on_hover_text("Wind is interpolated between layers, and constant above and below them").clicked() {
+            let altitude = wind.layers.iter().map(|layer| layer.altitude + 100.0).fold(0.0, f64::max);
+            wind.layers.push(sim::WindLayer { altitude, speed: 5.0, direction: 270.0 });
+        }
+    }
+
+    // Monte Carlo landing dispersion, shown on a map with the ground track. Runs on request since it's slow
+    fn dispersion_ui(&mut self, ui: &mut egui::Ui, results: &[sim::SimData]) {
+        ui.horizontal(|ui| {
+            let uncertainty = &mut self.simulation.uncertainty;
+            ui.label("Monte Carlo runs:");
+            integer_edit_field(ui, &mut uncertainty.runs);
+            ui.label("Standard deviations. Cd:").on_hover_text("Relative to the nominal value, e.g. 0.1 for 10 %");
+            ui::number_edit_field(ui, &mut uncertainty.cd);
+            ui.label("Mass:");
+            ui::number_edit_field(ui, &mut uncertainty.mass);
+            ui.label("Wind speed:");
+            ui::number_edit_field(ui, &mut uncertainty.wind_speed);
+            ui.label("Wind direction [°]:");
+            ui::number_edit_field(ui, &mut uncertainty.wind_direction);
+        });
+
+        let settings = self.get_descent_settings();
+        let canopy_area = self.get_canopy_area();
+        if ui.button("Run Monte Carlo").clicked() {
+            self.dispersion = self.get_landing_dispersion().map(|dispersion| (settings.clone(), canopy_area, dispersion));
+        }
+
+        // Results of other settings are outdated
+        let dispersion = self.dispersion.as_ref()
+            .filter(|(used_settings, used_area, _)| *used_settings == settings && *used_area == canopy_area)
+            .map(|(_, _, dispersion)| dispersion);
+        if let Some(dispersion) = dispersion {
+            ui.label(format!("95 % of the landings are within a {:.0} m by {:.0} m ellipse around {:.0} m east, {:.0} m north of the start. Long axis at {:.0}° from north",
+                2.0 * dispersion.semi_major, 2.0 * dispersion.semi_minor, dispersion.mean.x, dispersion.mean.y, dispersion.orientation));
+        }
+
+        // East and north of the start in m
+        egui_plot::Plot::new("sim_ground_track").height(400.0).data_aspect(1.0).legend(egui_plot::Legend::default()).show(ui, |plot_ui| {
+            let track: egui_plot::PlotPoints = results.iter().map(|dat| [dat.position.x, dat.position.y]).collect();
+            plot_ui.line(egui_plot::Line::new(track).width(2.0).name("Ground track"));
+
+            if let Some(dispersion) = dispersion {
+                let landings: egui_plot::PlotPoints = dispersion.landings.iter().map(|pt| [pt.x, pt.y]).collect();
+                plot_ui.points(egui_plot::Points::new(landings).radius(2.0).name("Monte Carlo landings"));
+                let ellipse: egui_plot::PlotPoints = dispersion.ellipse(72).iter().map(|pt| [pt.x, pt.y]).collect();
+                plot_ui.line(egui_plot::Line::new(ellipse).width(2.0).name("95 % ellipse"));
+            }
+        });
+    }
+
+    // Stages with the canopy of a reefed design use the reefing of the design
+    fn stage_ui(ui: &mut egui::Ui, stage: &mut sim::Stage, idx: usize, canopy_area: f64, design_reefed: bool, use_imperial: bool) {
+        ui.horizontal(|ui| {
+            let mut own_area = stage.area.is_some();
+            if ui.checkbox(&mut own_area, "Own canopy area").on_hover_text("Otherwise the canopy of this design is used").changed() {
+                stage.area = if own_area { Some(canopy_area) } else { None };
+            }
+            match &mut stage.area {
+                Some(area) => { ui::number_edit_field(ui, area); },
+                None => { ui.label(format!("{:.3}", canopy_area)); },
+            }
+            ui.label("m². Cd:");
+            ui::number_edit_field(ui, &mut stage.cd);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Deploys:");
+            Self::trigger_ui(ui, &mut stage.trigger, format!("stage_trigger_{}", idx), use_imperial);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Canopy fill constant:");
+            ui::number_edit_field(ui, &mut stage.fill_constant).on_hover_text("The canopy inflates while travelling this many nominal diameters. 0 opens instantly");
+            ui.label("Area growth exponent:");
+            ui::number_edit_field(ui, &mut stage.area_growth_exponent).on_hover_text("Drag area grows with the filled fraction to this power. Higher values open later and more abruptly");
+        });
+
+        if design_reefed && stage.area.is_none() {
+            ui.label("Reefed as set in the Design tab");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            let mut reefed = stage.reefing.is_some();
+            if ui.checkbox(&mut reefed, "Reefed").changed() {
+                stage.reefing = if reefed { Some(sim::Reefing { drag_area_ratio: 0.3, disreef: sim::Trigger::Time(2.0) }) } else { None };
+            }
+            if let Some(reefing) = &mut stage.reefing {
+                ui.label("Reefed / full CdS:");
+                ui::number_edit_field(ui, &mut reefing.drag_area_ratio);
+                ui.label("Disreefs:");
+                Self::trigger_ui(ui, &mut reefing.disreef, format!("stage_disreef_{}", idx), use_imperial);
+            }
+        });
+    }
+
+    // Time triggers count from the previous event, see sim::Stage
+    fn trigger_ui(ui: &mut egui::Ui, trigger: &mut sim::Trigger, id_source: String, use_imperial: bool) {
+        egui::ComboBox::from_id_source(id_source)
+            .selected_text(trigger.to_string())
+            .show_ui(ui, |ui| {
+                for option in [sim::Trigger::Altitude(300.0), sim::Trigger::Time(0.0), sim::Trigger::Velocity(20.0)] {
+                    let selected = std::mem::discriminant(trigger) == std::mem::discriminant(&option);
+                    if ui.selectable_label(selected, option.to_string()).clicked() && !selected {
+                        *trigger = option;
+                    }
+                }
+            }
+        );
+
+        match trigger {
+            sim::Trigger::Altitude(altitude) => ui::length_slider_no_limit(ui, altitude, use_imperial, 0.0..=5000.0, &length::meter, &length::foot),
+            sim::Trigger::Time(delay) => ui::length_slider(ui, delay, use_imperial, 0.0..=30.0, &si::time::second, &si::time::second),
+            sim::Trigger::Velocity(speed) => ui::length_slider(ui, speed, use_imperial, 0.0..=100.0, &si::velocity::meter_per_second, &si::velocity::foot_per_second),
+        };
+    }
+
+    pub fn get_3d_data(&self) -> Vec<three_d::CpuMesh> {
+        // Go through and generate the correct colors and mesh for 3D rendering...
+        let mut result = vec![];
+
+        // Note: This generates the mesh every single time, without checking if it's identical to the last time.
+
+        // Save everything in one mesh
+        let mut triangle_indices: Vec<u32> = vec![];
+        let mut new_colors: Vec<three_d::Srgba> = vec![];
+        let mut chute_coords: Vec<three_d::Vector3<f32>> = vec![];
+
+        let mut bounds_min = [f64::INFINITY, f64::INFINITY];
+        let mut bounds_max = [f64::NEG_INFINITY, f64::NEG_INFINITY];
+
+        for section in &self.chute_sections {
+            //let mut chute_cross = vec![]; 
+
+            let (chute_cross, sec_indices) = section.get_cross_section_with_indices(60, true);
+
+            let (min, max) = chute_cross.bounds();
+
+            bounds_min[0] = bounds_min[0].min(min.x);
+            bounds_min[1] = bounds_min[1].min(min.y);
+
+            bounds_max[0] = bounds_max[0].max(max.x);
+            bounds_max[1] = bounds_max[1].max(max.y);
+            
+            if chute_cross.points.len() < 2 {
+                continue;
+            }
+
+            let mut chute_cross: Vec<three_d::Vector2<f32>> = chute_cross.points.iter().map(|pt| three_d::vec2(pt.x as f32, pt.y as f32)).collect();
+            let num_gores = section.gores as usize;
+
+            if num_gores == 0 {
+                // If zero
+                continue;
+            }
+
+            let color_map: Vec<three_d::Srgba> = if section.colors.is_empty() {
+                vec![three_d::Srgba::new(255, 79, 0, 255)]
+            } else {
+                section.colors.iter().map(|rgb| ui::rgb_to_srgba(rgb)).collect()
+            };
+
+            // Split each gore into columns, so modifiers that change the middle of the gore are visible
+            const NUM_COLUMNS: usize = 8;
+            let last_idx = chute_cross.len()-1;
+            let column_limits = section.get_gore_index_limits(60, NUM_COLUMNS);
+            let column_limits = if column_limits.len() == NUM_COLUMNS + 1 { column_limits } else { vec![(0, last_idx); NUM_COLUMNS + 1] };
+
+            let num_points_per_gore = chute_cross.len();
+
+            for gore_idx in 0..num_gores {
+                let angle_left = gore_idx as f32 / (num_gores as f32) * core::f32::consts::PI * 2.0;
+                let angle_right = (gore_idx + 1) as f32 / (num_gores as f32) * core::f32::consts::PI * 2.0;
+                let color = color_map[gore_idx % color_map.len()];
+                let gore_offset = chute_coords.len() as u32;
+
+                // Every other gore is mirrored
+                let limits = |column: usize| if gore_idx % 2 == 0 { column_limits[NUM_COLUMNS - column] } else { column_limits[column] };
+
+                for column in 0..=NUM_COLUMNS {
+                    let t = column as f32 / NUM_COLUMNS as f32;
+                    for pt in chute_cross.iter() {
+                        let left = three_d::vec3(pt.x * angle_left.cos(), pt.y, pt.x * angle_left.sin());
+                        let right = three_d::vec3(pt.x * angle_right.cos(), pt.y, pt.x * angle_right.sin());
+                        chute_coords.push(left * (1.0 - t) + right * t);
+                        new_colors.push(color);
+                    }
+                }
+
+                // Generate triangles
+                for column in 0..NUM_COLUMNS {
+                    // Set minimum and maximum in order to show gore geometry modifiers
+                    let (min_left, max_left) = limits(column);
+                    let (min_right, max_right) = limits(column + 1);
+                    let offset_left = gore_offset + (column * num_points_per_gore) as u32;
+                    let offset_right = gore_offset + ((column + 1) * num_points_per_gore) as u32;
+
+                    for point_idx in 0..(num_points_per_gore-1) {
+                        // Get four points forming a square
+                        let pt_left0 = offset_left + (point_idx).max(min_left).min(max_left) as u32;
+                        let pt_left1 = offset_left + (point_idx + 1).max(min_left).min(max_left) as u32;
+                        let pt_right0 = offset_right + (point_idx).max(min_right).min(max_right) as u32;
+                        let pt_right1 = offset_right + (point_idx + 1).max(min_right).min(max_right) as u32;
+
+                        // Two triangles forming a square
+                        // Order counterclockwise
+                        triangle_indices.append(&mut vec![pt_left0, pt_right0, pt_left1,
+                                                         pt_right0, pt_right1, pt_left1]);
+                    }
+                }
+            }
+
+    
+            //for idx in 0..cpu_mesh.vertex_count() {
+            //    new_colors.push(Srgba::new((((idx % (5 * num_points_per_gore))/num_points_per_gore * 255) % 256) as u8 , 0 as u8, 0 as u8, 255))
+            //}
+
+        }
+
+        // Scale and offset chute_coords:
+        let scaling = 0.5 / (bounds_max[0]).max(0.5 * (bounds_max[1] - bounds_min[1])).max(0.01) as f32; // Scaling factor
+        let offset_y = ((bounds_max[1] + bounds_min[1]) * 0.5) as f32;
+
+        for pt in chute_coords.iter_mut() {
+            (*pt).y -= offset_y;
+            //*pt *= scaling;
+        }
+
+        if chute_coords.is_empty() {
+
+            // If no geometry present, add a triangle
+            chute_coords.push(three_d::vec3(0.0, 0.0, 0.0));
+            chute_coords.push(three_d::vec3(0.0, 0.0, 0.0));
+            chute_coords.push(three_d::vec3(0.0, 0.0, 0.0));
+
+            new_colors.push(three_d::Srgba::new(0, 0, 0, 0));
+            new_colors.push(three_d::Srgba::new(0, 0, 0, 0));
+            new_colors.push(three_d::Srgba::new(0, 0, 0, 0));
+
+            triangle_indices.append(&mut vec![0, 1, 2]);
+        }
+
+        let mut cpu_mesh = three_d::CpuMesh {
+            positions: three_d::Positions::F32(chute_coords),
+            indices: three_d::Indices::U32(triangle_indices),
+            ..Default::default()
+        };
+
+        cpu_mesh.colors = Some(new_colors);
+    
+        cpu_mesh.compute_normals();
+        
+        result.push(cpu_mesh);
+
+        result
+    }
+}
+
+impl Default for ChuteDesigner {
+    fn default() -> Self {
+        let context = Self::default_context();
+
+        // TODO: make math functions work without prefix
+
+        let input1 = InputValue {description: "".into(), id: "input1".into(), range: 0.0..=10.0, unit: StandardUnit::MeterFoot, value: 0.0, default_value: 0.0};
+        let input2 = InputValue {description: "".into(), id: "input2".into(), range: 0.0..=10.0, unit: StandardUnit::MeterFoot, value: 0.0, default_value: 0.0};
+
+        let input3 = InputValue {description: "Parachute Diameter".into(), id: "diameter".into(), range: 0.0..=10.0, unit: StandardUnit::MeterFoot, value: 1.0, default_value: 1.0};
+        let input4 = InputValue {description: "height / diameter of parachute".into(), id: "height_ratio".into(), range: 0.0..=1.0, unit: StandardUnit::UnitLess, value: 0.7, default_value: 0.7};
+        let input5 = InputValue {description: "vent_diameter / diameter of parachute".into(), id: "vent_ratio".into(), range: 0.0..=1.0, unit: StandardUnit::UnitLess, value: 0.2, default_value: 0.2};
+
+
+        let param1 = ParameterValue {display_unit: StandardUnit::MeterFoot, id: "param1".into(), expression: "input1*2".into()};
+        let param2 = ParameterValue {display_unit: StandardUnit::MeterFoot, id: "param2".into(), expression: "input2*2".into()};
+        let param3 = ParameterValue {display_unit: StandardUnit::MeterFoot, id: "param3".into(), expression: "param1+param2".into()};
+
+        let mut section1 = ChuteSection::new_circular();
+        match &mut section1.section_type {
+            ChuteSectionType::Circular(sec) => {
+                sec.expressions[0] = "vent_ratio*diameter".into();
+                sec.expressions[1] = "height_ratio*diameter".into();
+                sec.expressions[2] = "diameter".into();
+                sec.expressions[3] = "0".into();
+            },
+            _ => {}
+        }
+
+        Self { 
+            format_version: file_format::FORMAT_VERSION,
+            name: "Untitled Parachute".to_owned(),
+            gores: 8,
+            diameter: 1.0,
+            instructions: vec!["Cut out fabric".to_owned()],
+            fabric: FabricSelector::new(),
+            use_global_seam_allowance: true,
+            global_seam_allowance: 0.01,
+            fabric_width: None,
+            suspension_lines: SuspensionLines::default(),
+            tape_linear_density: 0.0,
+            pack_density: DEFAULT_PACK_DENSITY,
+            reefing: None,
+            simulation: sim::SimSettings::default(),
+            measurements: vec![],
+            input_values: vec![input1, input2, input3, input4, input5],
+            parameter_values: vec![param1, param2, param3],
+            evaluator_context: context,
+            chute_sections: vec![section1],
+            dispersion: None,
+            pattern_summary: None,
+        }
+    }
+}
+
+
+fn example_write_dxf() -> Result<(), Box<dyn std::error::Error>> {
+    // Define the vertices of the triangle
+    let vertices = [(0.0, 0.0), (1.0, 0.0), (0.5, 1.0)];
+
+    // Create a new DXF drawing
+    let mut drawing = Drawing::new();
+
+    // Create a polyline entity for the triangle
+    let mut polyline = Polyline::default();
+    polyline.set_is_closed(true); // Closed polyline for a triangle
+
+    // Add vertices to the polyline
+    for &(x, y) in &vertices {
+        let vertex = Vertex::new(dxf::Point::new(x, y, 0.0));
+        polyline.add_vertex(&mut drawing, vertex);
+    }
+
+    // Add the polyline to the drawing
+    drawing.add_entity(Entity::new(EntityType::Polyline(polyline)));
+
+    // Save the drawing to a DXF file
+    drawing.save_file("triangle.dxf")?;
+
+    println!("Triangle saved to triangle.dxf");
+
+    Ok(())
+}
+
+
+// An array of 2D points representing a line in a pattern piece. Seam allowance is constant throughout a segment
+// All dimensions given in mm
+#[derive(Clone, Debug)]
+struct Segment {
+    points: Vec<na::Vector2<f64>>,
+    seam_allowance: f64,
+}
+
+impl Segment {
+    fn new() -> Self {
+        Self { points: vec![], seam_allowance: 0.0 }
+    }
+
+    fn from_vec(points: Vec<na::Vector2<f64>>, seam_allowance: f64) -> Self {
+        Self { points: points, seam_allowance: seam_allowance }
+    }
+
+    fn new_with_allowance(seam_allowance: f64) -> Self {
+        Self { points: vec![], seam_allowance: seam_allowance }
+    }
+
+    fn set_seam_allowance(&mut self, seam_allowance: f64) {
+        self.seam_allowance = seam_allowance;
+    }
+
+    fn add_point(&mut self, point: na::Vector2<f64>) {
+        self.points.push(point);
+    }
+
+    fn add_point_xy(&mut self, x: f64, y: f64) {
+        self.points.push(na::Vector2::new(x, y));
+    }
+
+    fn mirror_x(&self) -> Self {
+        // returns a copy mirrored around the X axis
+        let mut new = self.clone();
+        for point in new.points.iter_mut() {
+            point.x = -point.x;
+        }
+        new
+    }
+
+    fn reverse(&self) -> Self {
+        let mut new = self.clone();
+        new.points.reverse();
+        new
+    }
+
+    fn scale(&mut self, scale_x: f64, scale_y: f64) {
+        // Scale around origin
+        for point in self.points.iter_mut() {
+            point.x = point.x * scale_x;
+            point.y = point.y * scale_y;
+        }
+    }
+
+    fn get_point(&self, idx: usize) -> na::Vector2<f64> {
+        self.points[idx].clone()
+    }
+
+    fn get_first_point(&self) -> na::Vector2<f64> {
+        self.points.first().unwrap().clone()
+    }
+
+    fn get_last_point(&self) -> na::Vector2<f64> {
+        self.points.last().unwrap().clone()
+    }
+
+    fn all_equal(&self) -> bool {
+        // check if all points are the same
+        // Also false if 0 or 1 points present
+        if self.points.len() <= 1 {
+            return false;
+        }
+
+        let pt1 = self.points.first().unwrap().clone();
+
+        for pt in &self.points {
+            if (pt1 - pt).norm_squared() > 1e-6 {
+                // not equal
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub struct PatternPiece {
+    segments: Vec<Segment>,
+    points: Vec<na::Vector2<f64>>, // Points not including seams
+    computed_points: Vec<na::Vector2<f64>>, // Final points including seams
+    fabric_area: f64, // Area in mm2, includes seam allowances
+    chute_area: f64, // Area in mm2, not including seam allowances
+    count: u16, // Number of duplicate pattern pieces
+    corner_cutout: bool,
+    name: String,
+    holes: Vec<PatternPiece>, // Cutouts inside the piece, e.g. vents
+    is_hole: bool, // Seam allowance goes inwards
+}
+
+// Generic arbitrary 2D pattern piece
+// Segments are used to allow for different seam allowances
+// All the segments are connected together
+// Segments should be defined in counterclockwise direction. Clockwise pieces are detected and offset in the correct direction
+// Points connecting pieces can be duplicated, but not necessary
+// Edge joining two segments is given seam allowance of previous segment
+impl PatternPiece {
+    fn new() -> PatternPiece {
+        Self { segments: vec![], points: vec![], computed_points: vec![], fabric_area: 0.0, chute_area: 0.0, name: "pattern".into(), count: 1, corner_cutout: true, holes: vec![], is_hole: false}
+    }
+
+    fn add_segment(&mut self, seg: Segment) {
+        self.segments.push(seg);
+    }
+
+    fn set_corner_cutout(&mut self, cutout: bool) {
+        self.corner_cutout = cutout;
+    }
+
+    fn add_hole(&mut self, mut hole: PatternPiece) {
+        hole.is_hole = true;
+        self.holes.push(hole);
+    }
+
+    // Outline followed by the outlines of the holes. Requires compute() first
+    fn get_contours(&self, including_seams: bool) -> Vec<Vec<na::Vector2<f64>>> {
+        let mut contours = vec![if including_seams { self.computed_points.clone() } else { self.points.clone() }];
+        for hole in &self.holes {
+            contours.append(&mut hole.get_contours(including_seams));
+        }
+        contours
+    }
+
+    // Split along a line through origin, sloped by angle (in rad). Returns the parts below and above the line.
+    // The new edge gets allowance_below on the lower part and allowance_above on the upper part
+    fn split(&self, origin: na::Vector2<f64>, angle: f64, allowance_below: f64, allowance_above: f64) -> (PatternPiece, PatternPiece) {
+        (self.clip(origin, angle, false, allowance_below), self.clip(origin, angle, true, allowance_above))
+    }
+
+    // Keep the part on one side of a line. Remaining edges keep the seam allowance of their segment
+    fn clip(&self, origin: na::Vector2<f64>, angle: f64, keep_above: bool, cut_allowance: f64) -> PatternPiece {
+        let height_above = |pt: &na::Vector2<f64>| pt.y - (origin.y + (pt.x - origin.x) * angle.tan());
+        let inside = |pt: &na::Vector2<f64>| if keep_above { height_above(pt) >= -1e-9 } else { height_above(pt) <= 1e-9 };
+
+        // Each point is tagged with the segment of the edge starting at it. The cut edge gets its own tag
+        let cut_tag = self.segments.len();
+        let vertices: Vec<(na::Vector2<f64>, usize)> = self.segments.iter().enumerate()
+            .flat_map(|(seg_idx, seg)| seg.points.iter().map(move |pt| (*pt, seg_idx)))
+            .collect();
+
+        let mut clipped = vec![];
+
+        for (idx, &(pt, tag)) in vertices.iter().enumerate() {
+            let next = vertices[(idx + 1) % vertices.len()].0;
+            let (h0, h1) = (height_above(&pt), height_above(&next));
+
+            match (inside(&pt), inside(&next)) {
+                (true, true) => clipped.push((pt, tag)),
+                (true, false) => {
+                    clipped.push((pt, tag));
+                    clipped.push((pt + (next - pt) * (h0 / (h0 - h1)), cut_tag));
+                },
+                (false, true) => clipped.push((pt + (next - pt) * (h0 / (h0 - h1)), tag)),
+                (false, false) => {},
+            }
+        }
+
+        let mut piece = PatternPiece::new();
+        piece.corner_cutout = self.corner_cutout;
+        piece.count = self.count;
+        piece.name = self.name.clone();
+
+        if clipped.len() < 3 {
+            return piece;
+        }
+
+        // Start at a segment boundary so segments aren't split in two
+        if let Some(start) = (0..clipped.len()).find(|&idx| clipped[idx].1 != clipped[(idx + clipped.len() - 1) % clipped.len()].1) {
+            clipped.rotate_left(start);
+        }
+
+        let mut segment: Option<(usize, Segment)> = None;
+        for (pt, tag) in clipped {
+            match &mut segment {
+                Some((current_tag, seg)) if *current_tag == tag => seg.add_point(pt),
+                _ => {
+                    if let Some((_, seg)) = segment.take() {
+                        piece.add_segment(seg);
+                    }
+                    let allowance = self.segments.get(tag).map_or(cut_allowance, |seg| seg.seam_allowance);
+                    segment = Some((tag, Segment::from_vec(vec![pt], allowance)));
+                }
+            }
+        }
+        if let Some((_, seg)) = segment {
+            piece.add_segment(seg);
+        }
+
+        piece
+    }
+
+    // Area of the outline without seam allowance, before computing
+    fn get_outline_area(&self) -> f64 {
+        let points: Vec<na::Vector2<f64>> = self.segments.iter().flat_map(|seg| seg.points.iter().cloned()).collect();
+        if points.len() < 3 {
+            return 0.0;
+        }
+        let mut sum = 0.0;
+        for idx in 0..points.len() {
+            let (pt0, pt1) = (points[idx], points[(idx + 1) % points.len()]);
+            sum += pt0.x * pt1.y - pt0.y * pt1.x;
+        }
+        sum / 2.0
+    }
+
+    fn compute(&mut self) {
+        // Compute seam allowances etc
+        self.computed_points = vec![];
+        self.points = vec![];
+
+        for hole in self.holes.iter_mut() {
+            hole.compute();
+        }
+
+
+        // only process non_empty segments
+        let mut segments = vec![];
+
+        for seg in &self.segments {
+            if !seg.points.is_empty() {
+                segments.push(seg.clone());
+            }
+        }
+
+        if segments.is_empty() {
+            return;
+        }
+
+        // Use first point, and go through in reverse order
+        let mut last_pt = segments.first().unwrap().points.first().unwrap().clone(); 
+
+        for (seg_idx,seg) in segments.iter_mut().rev().enumerate() {
+            let mut points_to_delete: Vec<usize> = vec![];
+            let seg_len = seg.points.len();
+            for (pt_idx, pt) in seg.points.iter().rev().enumerate() {
+                if (pt - last_pt).norm_squared() < 1e-6 {
+                    points_to_delete.push(seg_len - 1 - pt_idx);
+                } else {
+                    last_pt = pt.clone();
+                }
+            }
+            
+            // Remove duplicates from back
+            for delete_idx in points_to_delete.iter() {
+                seg.points.remove(delete_idx.clone());
+            }
+        }
+
+        // Remove empty segments again
+        segments.retain(|seg| !seg.points.is_empty());
+
+        if segments.is_empty() {
+            return;
+        }
+
+        // Normals point to the right of the outline, which is outwards for counterclockwise pieces.
+        // Flip them if the winding is the other way, or inwards for holes
+        let outline: Vec<na::Vector2<f64>> = segments.iter().flat_map(|seg| seg.points.iter().cloned()).collect();
+        let direction = if (signed_area(&outline) < 0.0) != self.is_hole { -1.0 } else { 1.0 };
+
+        let mut allowance_prev = segments.last().unwrap().seam_allowance;
+        let mut allowance_next = segments.first().unwrap().seam_allowance;
+
+        let mut prev_pt = segments.last().unwrap().points.last().unwrap().clone(); // final point
+
+        for (seg_idx, seg) in segments.iter().enumerate() {
+            self.points.append(&mut seg.points.clone()); // Base shape without seam allowance
+            let seg_idx_next = (seg_idx + 1) % segments.len();
+
+            for idx in 0..seg.points.len() {
+                // Corner is first point in segment
+                let is_corner = idx == 0;
+                
+                if is_corner && seg_idx != 0 {
+                    allowance_prev = allowance_next;
+                    allowance_next = seg.seam_allowance;
+                }
+
+                let p0 = prev_pt;
+                let p1 = seg.get_point(idx);
+                let p2 = if idx < (seg.points.len() - 1) { seg.get_point(idx + 1) } else { segments.get(seg_idx_next).unwrap().get_first_point() };
+                prev_pt = p1;
+                //println!("points: {:?}, {:?}, {:?}", p0, p1, p2);
+
+                // Based on https://stackoverflow.com/questions/3749678/expand-fill-of-convex-polygon
+                let v01 = p1 - p0;
+                let v12 = p2 - p1;
+
+                let v01_norm = v01.norm();
+                let v12_norm = v12.norm();
+
+                // Rotate clockwise and normalise
+                let n01 = vec2(v01.y, -v01.x) / v01_norm * direction;
+                let n12 = vec2(v12.y, -v12.x) / v12_norm * direction;
+
+                let d = v12.dot(&n01);
+
+                if v01_norm < 1e-6 && v12_norm < 1e-6 {
+                    // All points are at the same position
+                    self.computed_points.push(p0);
+                }
+                else if d.abs() < 1e-6 || v01_norm < 1e-6 || v12_norm < 1e-6 {
+                    // Line is parallel, just offset with n01 with average offset
+
+                    let used_norm = if v01_norm < 1e-6 { n12 } else { n01 };
+
+                    let p0_offset_prev = p0 + used_norm * allowance_prev;
+                    let p0_offset_next = p0 + used_norm * allowance_next;
+
+                    if self.corner_cutout {
+                        // Add both points
+                        self.computed_points.push(p0_offset_prev);
+                        self.computed_points.push(p0_offset_next);
+                    } else {
+                        // Average
+                        self.computed_points.push((p0_offset_prev + p0_offset_next) * 0.5);
+                    }
+                } else {
+                    // Add to original points to define line
+                    let p0_offset_prev = p0 + n01 * allowance_prev;
+                    let p1_offset_prev = p1 + n01 * allowance_prev;
+                    let p1_offset_next = p1 + n12 * allowance_next;
+                    let p2_offset_next = p2 + n12 * allowance_next;
+
+                    // Find intersection
+                    if self.corner_cutout && (is_corner) {
+                        // Make corner cutout
+                        self.computed_points.push(p1_offset_prev);
+                        self.computed_points.push(p1);
+                        self.computed_points.push(p1_offset_next);
+                    } else {
+                        let a1 = p1_offset_prev.x - p0_offset_prev.x;
+                        let b1 = p1_offset_next.x - p2_offset_next.x;
+                        let c1 = p1_offset_next.x - p0_offset_prev.x;
+    
+                        let a2 = p1_offset_prev.y - p0_offset_prev.y;
+                        let b2 = p1_offset_next.y - p2_offset_next.y;
+                        let c2 = p1_offset_next.y - p0_offset_prev.y;
+
+                        let t = (b1*c2 - b2*c1) / (a2*b1 - a1*b2);
+
+                        let new_pt = vec2(p0_offset_prev.x + t * (p1_offset_prev.x - p0_offset_prev.x), p0_offset_prev.y + t * (p1_offset_prev.y - p0_offset_prev.y));
+                        self.computed_points.push(new_pt);           
+                    }
+                }
+                allowance_prev = allowance_next;
+
+            }   
+        }
+
+        if let Some(first_pt) = self.computed_points.first() {
+            self.computed_points.push(first_pt.clone()); // wrap around
+        }
+    }
+
+    fn save_dxf(&mut self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.compute();
+
+        // Create a new DXF drawing
+        let mut drawing = Drawing::new();
+
+        // Create a polyline entity for the triangle
+        let mut polyline = Polyline::default();
+        polyline.set_is_closed(true); // Closed polyline for a triangle
+
+        // Add vertices to the polyline
+        for point in self.computed_points.iter() {
+            let vertex = Vertex::new(dxf::Point::new(point.x, point.y, 0.0));
+            polyline.add_vertex(&mut drawing, vertex);
+        }
+
+        // Add the polyline to the drawing
+        drawing.add_entity(Entity::new(EntityType::Polyline(polyline)));
+
+        // Save the drawing to a DXF file
+        drawing.save_file(filename)?;
+
+        println!("Triangle saved to {}", filename);
+
+        Ok(())
+
+    }
+
+    fn get_area(&self, including_seams: bool) -> f64 {
+        // Area of the outline minus the holes, independent of winding
+        let holes_area: f64 = self.holes.iter().map(|hole| hole.get_area(including_seams)).sum();
+
+        let points = if including_seams { &self.computed_points } else { &self.points };
+        if points.len() < 2 {
+            return 0.0
+        }
+
+        signed_area(points).abs() - holes_area
+    }
+
+    // Checks that the seam allowance outline is usable for cutting. Requires compute() first
+    fn check_seam_allowance(&self) -> Result<(), String> {
+        let base_area = signed_area(&self.points);
+        let computed_area = signed_area(&self.computed_points);
+
+        let allowances_positive = self.segments.iter().all(|seg| seg.seam_allowance >= 0.0);
+
+        if base_area.abs() > 1e-12 && (base_area.signum() != computed_area.signum() || (allowances_positive && !self.is_hole && computed_area.abs() < base_area.abs() - 1e-12)) {
+            return Err("seam allowance collapses".into());
+        }
+
+        if self_intersects(&self.computed_points) {
+            return Err("seam allowance outline intersects itself".into());
+        }
+
+        for hole in &self.holes {
+            hole.check_seam_allowance().map_err(|e| format!("{} in cutout", e))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Escape text for use in XML/SVG
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Signed area of a closed outline. Positive for counterclockwise
+// https://en.wikipedia.org/wiki/Shoelace_formula
+fn signed_area(points: &[na::Vector2<f64>]) -> f64 {
+    let mut sum = 0.0;
+    for idx in 0..points.len() {
+        let (pt0, pt1) = (points[idx], points[(idx + 1) % points.len()]);
+        sum += pt0.x * pt1.y - pt0.y * pt1.x;
+    }
+    sum / 2.0
+}
+
+// Check if any two non-neighbouring edges of a closed outline cross each other
+fn self_intersects(points: &[na::Vector2<f64>]) -> bool {
+    let mut edges = vec![];
+    for idx in 0..points.len() {
+        let (pt0, pt1) = (points[idx], points[(idx + 1) % points.len()]);
+        if (pt1 - pt0).norm_squared() > 1e-18 {
+            edges.push((pt0, pt1));
+        }
+    }
+
+    let cross = |o: na::Vector2<f64>, a: na::Vector2<f64>, b: na::Vector2<f64>| (a - o).perp(&(b - o));
+    let num_edges = edges.len();
+
+    for i in 0..num_edges {
+        let (a0, a1) = edges[i];
+        for (j, &(b0, b1)) in edges.iter().enumerate().skip(i + 2) {
+            if i == 0 && j == num_edges - 1 {
+                continue; // Neighbours through the wrap around
+            }
+
+            // Skip quickly when the bounding boxes don't overlap
+            if a0.x.max(a1.x) < b0.x.min(b1.x) || b0.x.max(b1.x) < a0.x.min(a1.x) || a0.y.max(a1.y) < b0.y.min(b1.y) || b0.y.max(b1.y) < a0.y.min(a1.y) {
+                continue;
+            }
+
+            // Only count proper crossings, touching points are allowed (e.g. corner cutouts)
+            let eps = 1e-12;
+            let (c0, c1) = (cross(a0, a1, b0), cross(a0, a1, b1));
+            if c0 * c1 < -eps && cross(b0, b1, a0) * cross(b0, b1, a1) < -eps {
+                return true;
+            }
+
+            // Collinear edges folding back over each other
+            if c0.abs() < eps && c1.abs() < eps {
+                let dir = (a1 - a0).normalize();
+                let (a_min, a_max) = (0.0, (a1 - a0).norm());
+                let (t0, t1) = ((b0 - a0).dot(&dir), (b1 - a0).dot(&dir));
+                if t0.max(t1).min(a_max) - t0.min(t1).max(a_min) > 1e-9 {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+// DXF layer names and sizes
+const DXF_LAYER_CUT: &str = "CUT";
+const DXF_LAYER_STITCH: &str = "STITCH";
+const DXF_LAYER_LABELS: &str = "LABELS";
+const DXF_LAYER_MARKS: &str = "MARKS"; // Grainlines and notches
+const DXF_TEXT_HEIGHT: f64 = 10.0; // mm, in drawing units
+const DXF_NOTCH_LENGTH: f64 = 0.005; // m
+
+// Pattern piece placed on an export sheet. Coordinates in m
+#[derive(Clone)]
+pub(crate) struct PlacedPiece {
+    pub(crate) label: String,
+    pub(crate) section: usize, // Index of the chute section the piece belongs to
+    pub(crate) count: u16, // Number of pieces to cut
+    pub(crate) area: f64, // Area of one piece including seam allowance (m2)
+    pub(crate) cut: Vec<Vec<na::Vector2<f64>>>, // Outlines including seam allowance, followed by cutouts
+    pub(crate) stitch: Vec<Vec<na::Vector2<f64>>>, // Outlines without seam allowance
+    pub(crate) min: na::Vector2<f64>, // Bounding box
+    pub(crate) max: na::Vector2<f64>,
+}
+
+impl PlacedPiece {
+    // Vertical line with arrow heads through the middle half of the piece. Gores are cut with the grain along their center line
+    fn grainline(&self) -> Vec<(na::Vector2<f64>, na::Vector2<f64>)> {
+        let center = (self.min + self.max) * 0.5;
+        let half_length = (self.max.y - self.min.y) * 0.25;
+        let arrow = (half_length * 0.1).min(0.02);
+
+        let top = center + na::Vector2::new(0.0, half_length);
+        let bottom = center - na::Vector2::new(0.0, half_length);
+
+        vec![
+            (bottom, top),
+            (top, top + na::Vector2::new(-arrow, -arrow)),
+            (top, top + na::Vector2::new(arrow, -arrow)),
+            (bottom, bottom + na::Vector2::new(-arrow, arrow)),
+            (bottom, bottom + na::Vector2::new(arrow, arrow)),
+        ]
+    }
+
+    // Horizontal ticks where the outer stitch line crosses mid height, so side seams of neighbouring gores can be matched up
+    fn notches(&self, length: f64) -> Vec<(na::Vector2<f64>, na::Vector2<f64>)> {
+        let Some(outline) = self.stitch.first() else {
+            return vec![];
+        };
+        let y = (self.min.y + self.max.y) * 0.5;
+        let half = na::Vector2::new(length * 0.5, 0.0);
+
+        let mut notches = vec![];
+        for (idx, p1) in outline.iter().enumerate() {
+            let p2 = outline[(idx + 1) % outline.len()];
+            if (p1.y < y) != (p2.y < y) {
+                let t = (y - p1.y) / (p2.y - p1.y);
+                let crossing = p1 + (p2 - p1) * t;
+                notches.push((crossing - half, crossing + half));
+            }
+        }
+
+        notches
+    }
+}
+
+pub(crate) struct PatternLayout {
+    pub(crate) pieces: Vec<PlacedPiece>,
+    pub(crate) width: f64, // Sheet size including padding
+    pub(crate) height: f64,
+}
+
+impl PatternLayout {
+    // Fraction of the sheet covered by pieces
+    pub(crate) fn utilization(&self) -> f64 {
+        let area: f64 = self.pieces.iter().map(|placed| placed.area * placed.count as f64).sum();
+        area / (self.width * self.height)
+    }
+}
+
+struct PatternPieceCollection {
+    pieces: Vec<PatternPiece>, // Pattern piece and number of each
+}
+
+impl PatternPieceCollection {
+    fn new() -> Self {
+        Self { pieces: vec![] }
+    }
+
+    fn get_area(&self, including_seams: bool) -> f64 {
+        let mut total_area = 0.0;
+
+        for piece in self.pieces.iter() {
+            total_area += piece.get_area(including_seams) * (piece.count as f64);
+        }
+
+        total_area
+    }
+}
+
+// Standard gore geometry with four sides and straight top/bottom section
+// If coords_left is None, the coords are mirrored on the left side
+// Note: Coordinates go from bottom to top for BOTH left and right sections
+struct Gore {
+    coords_right: Segment,
+    coords_left: Segment,
+    seam_allowances: (f64, f64, f64, f64), // Seams. Right, top, left, bottom
+}
+
+impl Gore {
+    fn new(coords_right: Segment, coords_left: Segment, seam_allowances: (f64, f64, f64, f64)) -> Self {
+        // Coords are defined from bottom to top
+        Self { coords_right: coords_right, coords_left: coords_left, seam_allowances: seam_allowances}
+    }
+
+    fn new_symmetric(coords_right: Segment, seam_allowances: (f64, f64, f64, f64)) -> Self {
+        Gore::new(coords_right.clone(), coords_right.clone().mirror_x(), seam_allowances)
+    }
+
+    fn get_pattern_piece(&self, corner_cutout: bool) -> PatternPiece {
+        let mut piece = PatternPiece::new();
+        // Add right segment, excluding top point
+
+        let mut segment_right = self.coords_right.clone();
+        segment_right.set_seam_allowance(self.seam_allowances.0);
+
+        let mut segment_left = self.coords_left.reverse();
+        segment_left.set_seam_allowance(self.seam_allowances.2);
+        
+        let mut segment_top = Segment::new_with_allowance(self.seam_allowances.1);
+        segment_top.add_point(segment_right.get_last_point());
+        segment_top.add_point(segment_left.get_first_point());
+
+        let mut segment_bottom = Segment::new_with_allowance(self.seam_allowances.3);
+        segment_bottom.add_point(segment_left.get_last_point());
+        segment_bottom.add_point(segment_right.get_first_point());
+        
+        piece.add_segment(segment_right);
+        piece.add_segment(segment_top);
+        piece.add_segment(segment_left);
+        piece.add_segment(segment_bottom);
+
+        piece
+    }
+}
+
+
+// Trait for different parachute types
+trait Parachute {
+    fn get_pieces(&self) -> PatternPieceCollection;
+    fn get_fabric_area(&self) -> f64;
+    fn get_parachute_area(&self) -> f64;
+    fn get_projected_area(&self) -> f64;
+    fn get_cd(&self) -> f64; // Drag coefficient, based on parachute_area
+    fn get_line_length(&self) -> f64;
+}
+
+struct ParachuteProject {
+    chute: Box<dyn Parachute>
+}
+
+
+// How the length of each suspension line is chosen
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LineLength {
+    Fixed(f64), // Length (m)
+    DiameterRatio(f64), // Line length / nominal diameter
+    Confluence(f64), // Distance from the skirt plane down to the confluence point (m)
+}
+
+impl std::fmt::Display for LineLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineLength::Fixed(_) => write!(f, "Fixed length"),
+            LineLength::DiameterRatio(_) => write!(f, "Ratio to nominal diameter"),
+            LineLength::Confluence(_) => write!(f, "Confluence distance"),
+        }
+    }
+}
+
+// Lines from the canopy skirt to the payload
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct SuspensionLines {
+    count: Option<u16>, // None for one line per gore of the section at the skirt
+    length: LineLength,
+    cord: Cord,
+    peak_load: f64, // Highest force on the riser, e.g. the opening shock (N)
+    safety_factor: f64,
+}
+
+impl Default for SuspensionLines {
+    fn default() -> Self {
+        Self { count: None, length: LineLength::DiameterRatio(1.0), cord: Cord::get_types()[0].clone(), peak_load: 100.0, safety_factor: 2.0 }
+    }
+}
+
+// Reefing line through rings along the skirt, holding the canopy partly closed until it is cut
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct CanopyReefing {
+    ratio: f64, // Reefing line length / skirt circumference
+    drag_area_ratio: Option<f64>, // Reefed / full CdS. None estimates it from the ratio
+    disreef: sim::Trigger, // Time counts from the deployment of the canopy
+}
+
+impl CanopyReefing {
+    // Without a measured value, the drag area is taken to scale with the square of the skirt diameter
+    fn get_drag_area_ratio(&self) -> f64 {
+        self.drag_area_ratio.unwrap_or(self.ratio.powi(2)).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for CanopyReefing {
+    fn default() -> Self {
+        Self { ratio: 0.3, drag_area_ratio: None, disreef: sim::Trigger::Time(2.0) }
+    }
+}
+
+// Steady descent rate measured in a drop test
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct DescentMeasurement {
+    payload_mass: f64, // Total descending mass (kg)
+    descent_rate: f64, // m/s
+    altitude: f64, // Altitude of the measurement (m)
+    temperature: Option<f64>, // Measured air temperature (K). None uses the standard atmosphere
+}
+
+impl DescentMeasurement {
+    fn get_drag(&self, nominal_area: f64, projected_area: f64) -> MeasuredDrag {
+        let density = sim::get_density(self.altitude, self.temperature);
+        let drag_area = sim::drag_area_from_descent_rate(self.payload_mass, self.descent_rate.max(1e-6), density);
+        MeasuredDrag {
+            density,
+            drag_area,
+            cd_nominal: drag_area / nominal_area.max(1e-9),
+            cd_projected: drag_area / projected_area.max(1e-9),
+        }
+    }
+}
+
+impl Default for DescentMeasurement {
+    fn default() -> Self {
+        Self { payload_mass: 1.0, descent_rate: 5.0, altitude: 0.0, temperature: None }
+    }
+}
+
+pub struct MeasuredDrag {
+    pub density: f64, // kg/m3
+    pub drag_area: f64, // CdS (m2)
+    pub cd_nominal: f64, // Based on the canopy area
+    pub cd_projected: f64, // Based on the projected area
+}
+
+pub struct LineSizing {
+    pub count: u16,
+    pub length: f64, // Length of each line (m)
+    pub total_length: f64, // m
+    pub mass: f64, // kg
+    pub required_strength: f64, // Strength each line needs for the peak load and safety factor (N)
+    pub cord: String,
+    pub cord_strength: f64, // N
+}
+
+impl LineSizing {
+    pub fn is_strong_enough(&self) -> bool {
+        self.cord_strength >= self.required_strength
+    }
+}
+
+// Mass of one chute section (kg)
+#[derive(Clone)]
+pub struct SectionMass {
+    pub fabric: f64, // Including seam allowances
+    pub tape: f64, // Reinforcement tape along the seams
+}
+
+#[derive(Clone)]
+pub struct MassBreakdown {
+    pub sections: Vec<SectionMass>,
+    pub lines: f64, // kg
+    pub packed_volume: f64, // m3
+}
+
+impl MassBreakdown {
+    pub fn total(&self) -> f64 {
+        self.sections.iter().map(|section| section.fabric + section.tape).sum::<f64>() + self.lines
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Fabric {
+    area_density_gsm: f64,
+    name: String,
+    roll_width: f64, // m
+    price_per_meter: f64, // Price of one linear meter of the roll, in any currency
+}
+
+impl Fabric {
+    fn new(gsm: f64, name: &str) -> Self {
+        Self { area_density_gsm: gsm, name: name.to_owned(), roll_width: DEFAULT_FABRIC_WIDTH, price_per_meter: 0.0 }
+    }
+
+    fn get_name_weight(&self, imperial: bool) -> String {
+        if imperial {
+            format!("{} ({:.1} oz)", self.name, self.area_density_gsm / 33.906)
+        } else {
+            format!("{} ({:.0} gsm)", self.name, self.area_density_gsm)
+        }
+    }
+
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+struct FabricSelector {
+    modified: bool,
+    selected_fabric: Fabric,
+    fabric_options: Vec<Fabric>,
+}
+
+impl FabricSelector {
+    fn new() -> Self {
+
+        let mut options = vec![];
+
+        options.push(Fabric::new(38.0, "Ripstop nylon"));
+        options.push(Fabric::new(48.0, "Ripstop nylon"));
+        options.push(Fabric::new(67.0, "Ripstop nylon"));
+
+        let default_fabric = Fabric::new(38.0, "Ripstop nylon");
+
+        Self { modified: false, fabric_options: options, selected_fabric: default_fabric }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl FabricSelector {
+
+    fn ui(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame, use_imperial: bool, id: u16) {
+        egui::ComboBox::from_id_source(id)
+            .width(200.0)
+            .selected_text(self.selected_fabric.get_name_weight(use_imperial))
+            .show_ui(ui, |ui| {
+                for (_idx, option) in self.fabric_options.iter().enumerate() {
+                    ui.selectable_value(&mut self.selected_fabric, option.clone(), option.get_name_weight(use_imperial));
+                }
+            }
+        );
+
+        ui.horizontal(|ui| {
+            ui.label("Roll width:");
+            let width_changed = ui::length_slider(ui, &mut self.selected_fabric.roll_width, use_imperial, 0.5..=3.0, &length::meter, &length::foot).changed();
+            ui.label("Price per m:");
+            let price_changed = ui.add(egui::DragValue::new(&mut self.selected_fabric.price_per_meter).speed(0.1).clamp_range(0.0..=f64::MAX)).changed();
+            self.modified |= width_changed || price_changed;
+
+            // Keep the values on the option, so they are not lost when another option is picked
+            if width_changed || price_changed {
+                let selected = &self.selected_fabric;
+                if let Some(option) = self.fabric_options.iter_mut().find(|option| option.name == selected.name && option.area_density_gsm == selected.area_density_gsm) {
+                    option.roll_width = selected.roll_width;
+                    option.price_per_meter = selected.price_per_meter;
+                }
+            }
+        });
+    }
+}
+
+impl Default for FabricSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Pieces cut from one fabric in one color
+struct FabricGroup<'a> {
+    fabric: &'a Fabric,
+    color: Option<[f32; 3]>,
+    pieces: Vec<PlacedPiece>,
+}
+
+// Fabric needed for the pieces of one fabric and color
+#[derive(Clone)]
+pub struct BomEntry {
+    pub fabric: String,
+    pub color: Option<[f32; 3]>, // None if the section has no colors
+    pub pieces: u32,
+    pub length: f64, // Linear meters of the roll
+    pub cost: f64,
+    pub nested: bool, // False if the length is estimated from bounding boxes, because the pieces don't fit on the roll
+}
+
+#[derive(Clone)]
+pub struct BillOfMaterials {
+    pub entries: Vec<BomEntry>,
+    pub mass: f64, // Canopy fabric mass including seam allowances (kg)
+}
+
+// Fabric and mass figures shown in the editor. Computed from one layout of the pattern, see ChuteDesigner::update_pattern_summary
+#[derive(Clone)]
+struct PatternSummary {
+    nesting: Result<Option<(f64, f64)>, String>,
+    bill_of_materials: Result<BillOfMaterials, String>,
+    mass: Result<MassBreakdown, String>,
+}
+
+impl BillOfMaterials {
+    // Length (m) and cost per fabric, summed over colors
+    pub fn fabric_totals(&self) -> Vec<(String, f64, f64)> {
+        let mut totals: Vec<(String, f64, f64)> = vec![];
+        for entry in &self.entries {
+            match totals.iter_mut().find(|(fabric, _, _)| *fabric == entry.fabric) {
+                Some(total) => {
+                    total.1 += entry.length;
+                    total.2 += entry.cost;
+                },
+                None => totals.push((entry.fabric.clone(), entry.length, entry.cost)),
+            }
+        }
+        totals
+    }
+
+    pub fn total_cost(&self) -> f64 {
+        self.entries.iter().map(|entry| entry.cost).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+    use crate::parachute::{Segment, PatternPiece, ChuteSection, ChuteSectionType, CircularChuteSection, ChuteDesigner, PlacedPiece, LineLength, CanopyReefing, DescentMeasurement};
+    use crate::sim::{self, Trigger};
+    use crate::materials::Cord;
+    use crate::geometry;
+    use crate::error::OpenChuteError;
+    extern crate nalgebra as na;
+
+    // Default design with its input values evaluated
+    fn default_designer() -> ChuteDesigner {
+        let mut designer = ChuteDesigner::default();
+        designer.update_calculations();
+        designer
+    }
+
+    #[test]
+    fn test_save_dxf() {
+        let mut seg = Segment::new();
+        seg.add_point_xy(0.0, 0.0);
+        seg.add_point_xy(1.0, 0.0);
+        seg.add_point_xy(0.5, 2.0);
+
+        let mut pat = PatternPiece::new();
+        pat.add_segment(seg);
+        pat.compute();
+        pat.save_dxf("tall_triangle.dxf").unwrap();
+    }
+
+    #[test]
+    fn test_mirror_scale() {
+        let mut seg = Segment::new();
+        seg.add_point_xy(0.0, 0.0);
+        seg.add_point_xy(1.0, 2.0);
+
+        println!("{:?}", seg);
+        let mut seg = seg.mirror_x(); // Returns a mirrored copy
+        assert_eq!(seg.points[1], na::Vector2::new(-1.0, 2.0));
+        println!("{:?}", seg);
+        seg.scale(2.0, 3.0);
+        assert_eq!(seg.points[1], na::Vector2::new(-2.0, 6.0));
+    }
+
+    #[test]
+    fn test_area() {
+        let mut seg = Segment::new();
+        // Funky triangle, should give area of 2
+        seg.add_point_xy(0.0, 0.0);
+        seg.add_point_xy(1.0, 0.0);
+        seg.add_point_xy(-0.5, 4.0);
+
+        let mut pat = PatternPiece::new();
+        pat.add_segment(seg);
+        pat.compute();
+
+        assert_eq!(pat.get_area(false), 1.0 * 4.0 * 0.5)
+    }
+
+    #[test]
+    fn test_split() {
+        let mut pat = PatternPiece::new();
+        pat.add_segment(Segment::from_vec(vec![na::Vector2::new(0.0, 0.0), na::Vector2::new(1.0, 0.0)], 0.01));
+        pat.add_segment(Segment::from_vec(vec![na::Vector2::new(1.0, 0.0), na::Vector2::new(1.0, 2.0)], 0.02));
+        pat.add_segment(Segment::from_vec(vec![na::Vector2::new(1.0, 2.0), na::Vector2::new(0.0, 2.0)], 0.03));
+        pat.add_segment(Segment::from_vec(vec![na::Vector2::new(0.0, 2.0), na::Vector2::new(0.0, 0.0)], 0.04));
+
+        let (mut below, mut above) = pat.split(na::Vector2::new(0.5, 0.5), 0.0, 0.1, 0.2);
+        below.compute();
+        above.compute();
+
+        assert!((below.get_area(false) - 0.5).abs() < 1e-9);
+        assert!((above.get_area(false) - 1.5).abs() < 1e-9);
+        // Cut edges get their own seam allowance
+        assert!(below.segments.iter().any(|seg| seg.seam_allowance == 0.1));
+        assert!(above.segments.iter().any(|seg| seg.seam_allowance == 0.2));
+    }
+
+    #[test]
+    fn test_seam_allowance() {
+        let section = ChuteSection::new_circular();
+        let mut pat = section.to_pattern_piece(20);
+
+        pat.corner_cutout = false;
+        pat.compute();
+
+        println!("{:?}", pat.computed_points);
+
+    }
+
+    #[test]
+    fn test_vent_cutout() {
+        // Single flat disk with a spill hole
+        let mut section = ChuteSection::new_circular();
+        section.gores = 1;
+        section.section_type = ChuteSectionType::Circular(CircularChuteSection {
+            line: geometry::Line { begin: na::Vector2::new(0.2, 0.0), end: na::Vector2::new(1.0, 0.0) },
+            expressions: Default::default(),
+        });
+
+        let mut pat = section.to_pattern_piece(720);
+        pat.compute();
+
+        assert_eq!(pat.get_contours(false).len(), 2);
+        let expected = PI * (1.0 - 0.2 * 0.2);
+        assert!((pat.get_area(false) - expected).abs() < 1e-3 * expected);
+        // Seam allowance goes into the hole
+        assert!(pat.holes[0].get_area(true).abs() < pat.holes[0].get_area(false).abs());
+    }
+
+    #[test]
+    fn test_circular_fullness() {
+        // Cylindrical band, 0.5 m tall
+        let mut section = ChuteSection::new_circular();
+        section.section_type = ChuteSectionType::Circular(CircularChuteSection {
+            line: geometry::Line { begin: na::Vector2::new(1.0, 0.0), end: na::Vector2::new(1.0, 0.5) },
+            expressions: Default::default(),
+        });
+        section.fullness = (0.1, 0.3);
+
+        let mut pat = section.to_pattern_piece(100);
+        pat.compute();
+
+        let gore_width = 2.0 * PI / 8.0;
+        let expected = 0.5 * gore_width * (1.1 + 1.3) / 2.0;
+        assert!((pat.get_area(false) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_seam_direction() {
+        // Clockwise square still gets its seam allowance on the outside
+        let mut pat = PatternPiece::new();
+        pat.set_corner_cutout(false);
+        pat.add_segment(Segment::from_vec(vec![na::Vector2::new(0.0, 0.0), na::Vector2::new(0.0, 1.0), na::Vector2::new(1.0, 1.0), na::Vector2::new(1.0, 0.0)], 0.1));
+        pat.compute();
+
+        assert!((pat.get_area(true) - 1.2 * 1.2).abs() < 1e-9);
+        assert!(pat.check_seam_allowance().is_ok());
+
+        // Notch narrower than twice the seam allowance folds the outline over itself
+        let mut notched = PatternPiece::new();
+        notched.set_corner_cutout(false);
+        let points = [(0.0, 0.0), (3.0, 0.0), (3.0, 2.0), (1.6, 2.0), (1.6, 0.5), (1.4, 0.5), (1.4, 2.0), (0.0, 2.0)];
+        notched.add_segment(Segment::from_vec(points.iter().map(|&(x, y)| na::Vector2::new(x, y)).collect(), 0.2));
+        notched.compute();
+
+        assert!(notched.check_seam_allowance().is_err());
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let result = ChuteDesigner::from_json("{\"name\": \"Not a parachute\"}");
+        assert!(matches!(result, Err(OpenChuteError::Parse(_))));
+    }
+
+    #[test]
+    fn test_pattern_marks() {
+        // Triangle with its stitch line inside the cut line
+        let stitch = vec![na::Vector2::new(0.0, 0.0), na::Vector2::new(1.0, 0.0), na::Vector2::new(0.5, 2.0)];
+        let placed = PlacedPiece { label: "#1".into(), section: 0, count: 8, area: 1.0, cut: vec![], stitch: vec![stitch], min: na::Vector2::new(-0.1, -0.1), max: na::Vector2::new(1.1, 2.1) };
+
+        let notches = placed.notches(0.01);
+        assert_eq!(notches.len(), 2);
+        for (start, end) in notches {
+            assert!((start.y - 1.0).abs() < 1e-9 && (end.y - 1.0).abs() < 1e-9);
+            assert!(((start.x + end.x) * 0.5 - 0.5).abs() > 0.2); // On the side seams
+        }
+
+        let grainline = placed.grainline();
+        assert!((grainline[0].0.x - 0.5).abs() < 1e-9 && (grainline[0].1.y - grainline[0].0.y - 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bill_of_materials() {
+        let mut designer = default_designer();
+
+        let bom = designer.get_bill_of_materials().unwrap();
+        let gores: u32 = designer.chute_sections.iter().map(|section| section.gores as u32).sum();
+        assert_eq!(bom.entries.iter().map(|entry| entry.pieces).sum::<u32>(), gores);
+        assert!(bom.entries.iter().all(|entry| entry.length > 0.0));
+
+        // Default fabric is 38 gsm
+        let (_, area_with_seams, _) = designer.get_gores();
+        assert!((bom.mass - area_with_seams * 0.038).abs() < 1e-9);
+
+        // The editor shows the same figures, computed once per design change
+        assert!(designer.pattern_summary.is_none());
+        designer.update_pattern_summary();
+        let summary = designer.pattern_summary.as_ref().unwrap();
+        assert_eq!(summary.bill_of_materials.as_ref().unwrap().mass, bom.mass);
+        assert_eq!(summary.nesting, Ok(None));
+    }
+
+    #[test]
+    fn test_export_svg() {
+        let mut designer = default_designer();
+        designer.name = "Chute <A&B>".into();
+
+        let path = std::env::temp_dir().join("openchute_test_export.svg");
+        designer.export_svg(path.clone()).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+
+        let layout = designer.layout_pattern(360, 0.05).unwrap();
+        let (width, height) = (layout.width * 1000.0, layout.height * 1000.0);
+        assert!(svg.contains(&format!("width=\"{:.3}mm\" height=\"{:.3}mm\" viewBox=\"0 0 {:.3} {:.3}\"", width, height, width, height)));
+        assert!(svg.contains("<title>Chute &lt;A&amp;B&gt;</title>"));
+
+        // Every contour has a cut and a stitch outline
+        let group = |id: &str| svg.split(&format!("<g id=\"{}\"", id)).nth(1).unwrap().split("</g>").next().unwrap().matches("<path").count();
+        assert_eq!(group("cut"), layout.pieces.iter().map(|placed| placed.cut.len()).sum::<usize>());
+        assert_eq!(group("stitch"), layout.pieces.iter().map(|placed| placed.stitch.len()).sum::<usize>());
+
+        assert!(path.with_extension("svg.chute").exists());
+    }
+
+    #[test]
+    fn test_export_dxf() {
+        let mut designer = default_designer();
+
+        let path = std::env::temp_dir().join("openchute_test_export.dxf");
+        designer.export_dxf(path.clone()).unwrap();
+        let drawing = dxf::Drawing::load_file(&path).unwrap();
+
+        let layers: Vec<&str> = drawing.layers().map(|layer| layer.name.as_str()).collect();
+        for name in [super::DXF_LAYER_CUT, super::DXF_LAYER_STITCH, super::DXF_LAYER_LABELS, super::DXF_LAYER_MARKS] {
+            assert!(layers.contains(&name));
+        }
+        for name in [super::DXF_LAYER_CUT, super::DXF_LAYER_STITCH, super::DXF_LAYER_LABELS] {
+            assert!(drawing.entities().any(|entity| entity.common.layer == name));
+        }
+    }
+
+    #[test]
+    fn test_export_pdf() {
+        let mut designer = default_designer();
+
+        let path = std::env::temp_dir().join("openchute_test_export.pdf");
+        designer.export_pdf(path.clone(), crate::pdf::PaperSize::Full).unwrap();
+        let single = printpdf::lopdf::Document::load(&path).unwrap().get_pages().len();
+
+        // Tiled pages come after the index page, the build sheet is the same
+        designer.set_fabric_width(Some(1.5));
+        designer.export_pdf(path.clone(), crate::pdf::PaperSize::A4).unwrap();
+        let tiled = printpdf::lopdf::Document::load(&path).unwrap().get_pages().len();
+        assert!(tiled > single + 1);
+    }
+
+    #[test]
+    fn test_mass_breakdown() {
+        let mut designer = default_designer();
+
+        let mass = designer.get_mass_breakdown().unwrap();
+        let (_, area_with_seams, _) = designer.get_gores();
+        assert!((mass.sections[0].fabric - area_with_seams * 0.038).abs() < 1e-9);
+        assert_eq!(mass.sections[0].tape, 0.0);
+        assert!(mass.lines > 0.0);
+        assert!((mass.packed_volume - mass.total() / 500.0).abs() < 1e-12);
+
+        // Tape adds mass along the seams
+        designer.tape_linear_density = 5.0;
+        assert!(designer.get_mass_breakdown().unwrap().sections[0].tape > 0.0);
+    }
+
+    #[test]
+    fn test_line_sizing() {
+        let mut designer = default_designer();
+
+        // One line per gore, as long as the nominal diameter
+        let sizing = designer.get_line_sizing();
+        assert_eq!(sizing.count, designer.chute_sections[0].gores);
+        assert!((sizing.length - designer.get_nominal_diameter()).abs() < 1e-9);
+        assert!((sizing.total_length - sizing.count as f64 * sizing.length).abs() < 1e-9);
+
+        // Confluence point as far below the skirt as the skirt radius, so the lines pull at 45°
+        let (radius, _) = designer.get_skirt().unwrap();
+        designer.suspension_lines.length = LineLength::Confluence(radius);
+        designer.suspension_lines.count = Some(4);
+        let sizing = designer.get_line_sizing();
+        assert!((sizing.length - radius * 2f64.sqrt()).abs() < 1e-9);
+
+        let share = designer.suspension_lines.peak_load * designer.suspension_lines.safety_factor / 4.0;
+        assert!((sizing.required_strength - share * 2f64.sqrt()).abs() < 1e-6);
+
+        let cord = Cord::lightest_with_strength(sizing.required_strength).unwrap();
+        assert!(cord.get_strength() >= sizing.required_strength);
+        assert!(Cord::lightest_with_strength(1e9).is_none());
+    }
+
+    #[test]
+    fn test_reefing() {
+        let mut designer = default_designer();
+        assert!(designer.get_reefing_line_length().is_none());
+
+        // The default cone has its skirt at a radius of 1 m
+        designer.reefing = Some(CanopyReefing { ratio: 0.5, drag_area_ratio: None, disreef: Trigger::Time(1.0) });
+        assert!((designer.get_skirt_circumference() - 2.0 * PI).abs() < 1e-9);
+        assert!((designer.get_reefing_line_length().unwrap() - PI).abs() < 1e-9);
+
+        // The design canopy in the simulation is reefed to a quarter of its drag area
+        let mut sim = designer.get_descent_sim();
+        let results = sim.simulate();
+        let stage = &sim.get_stage_results()[0];
+        let full_drag_area = designer.get_canopy_area() * 0.8;
+        let reefed = results.iter().filter(|dat| dat.time < stage.disreef_time.unwrap()).map(|dat| dat.drag_area).fold(0.0, f64::max);
+        assert!((reefed - 0.25 * full_drag_area).abs() < 1e-9);
+        assert!(stage.disreef_force.is_some());
+
+        // A polygonal skirt has a straight hem across each of the 8 gores, between the corners
+        let mut polygonal = ChuteSection::new_polygonal();
+        if let ChuteSectionType::Polygonal(sec) = &mut polygonal.section_type {
+            sec.add_line(); // Flat, 1 m nominal radius
+        }
+        designer.chute_sections[0] = polygonal;
+        designer.update_calculations();
+        let (corner_radius, _) = designer.get_skirt().unwrap();
+        assert!((designer.get_skirt_circumference() - 16.0 * corner_radius * (PI / 8.0).sin()).abs() < 1e-9);
+        assert!((designer.get_skirt_circumference() - 2.0 * PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_measured_drag() {
+        let mut designer = default_designer();
+
+        // The default cone is 2 m across at the skirt
+        assert!((designer.get_projected_area() - PI).abs() < 1e-9);
+
+        designer.measurements.push(DescentMeasurement { payload_mass: 1.0, descent_rate: 4.0, altitude: 0.0, temperature: None });
+        let drag = &designer.get_measured_drag()[0];
+        let drag_area = 2.0 * 9.80665 / (sim::get_atmosphere(0.0).density * 16.0);
+        assert!((drag.drag_area - drag_area).abs() < 1e-9);
+        assert!((drag.cd_nominal - drag_area / designer.get_canopy_area()).abs() < 1e-9);
+        assert!((drag.cd_projected - drag_area / PI).abs() < 1e-9);
+
+        // A standard temperature gives the standard density, warmer air is thinner and needs more drag for the same descent rate
+        designer.measurements[0].temperature = Some(288.15);
+        assert!((designer.get_measured_drag()[0].drag_area - drag_area).abs() < 1e-3 * drag_area);
+        designer.measurements[0].temperature = Some(308.15);
+        assert!(designer.get_measured_drag()[0].cd_nominal > drag.cd_nominal);
+    }
+}
@@ -0,0 +1,525 @@
+// PDF output of pattern layouts. Either a single page sized to the whole layout,
+// or tiled onto printer paper that can be taped together.
+
+use printpdf::{Mm, Point, Line, Polygon, PolygonMode, WindingOrder, PdfDocument, PdfDocumentReference, PdfLayerReference, IndirectFontRef, BuiltinFont, Color, Rgb, LineDashPattern};
+use printpdf::lopdf::content::Operation;
+
+use nalgebra::Vector2;
+
+use crate::error::OpenChuteError;
+use crate::parachute::PatternLayout;
+
+// Border of each tiled page that most printers can't print on
+const PAGE_MARGIN: f32 = 10.0; // mm
+// Printed area repeated on neighbouring pages, used for aligning the pages
+const PAGE_OVERLAP: f32 = 15.0; // mm
+const ALIGNMENT_CROSS_SIZE: f32 = 5.0; // mm
+// Strip at the bottom of each tiled page with the small calibration square
+const PAGE_FOOTER: f32 = 30.0; // mm
+// Strip below the layout on single page output with the calibration square and title block
+const TITLE_BLOCK_HEIGHT: f32 = 130.0; // mm
+const TITLE_BLOCK_MIN_WIDTH: f32 = 300.0; // mm
+const LINE_SPACING: f32 = 5.0; // mm, for 9 pt text
+// Smallest height of the layout overview below the title block of the index page
+const MIN_OVERVIEW_HEIGHT: f32 = 60.0; // mm
+// Approximate number of 9 pt Helvetica characters that fit on a line of the build sheet, per mm
+const CHARS_PER_MM: f32 = 0.55;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PaperSize {
+    Full, // One page with the size of the whole layout
+    A4,
+    Letter,
+}
+
+impl PaperSize {
+    // Portrait paper size in mm
+    fn dimensions(&self) -> Option<(f32, f32)> {
+        match self {
+            PaperSize::Full => None,
+            PaperSize::A4 => Some((210.0, 297.0)),
+            PaperSize::Letter => Some((215.9, 279.4)),
+        }
+    }
+
+    // Side length (mm) and label of the calibration square. Letter paper is measured in inches.
+    // The small square fits in the footer of tiled pages
+    fn calibration_square(&self, small: bool) -> (f32, &'static str) {
+        match (self, small) {
+            (PaperSize::Letter, false) => (101.6, "4 in"),
+            (PaperSize::Letter, true) => (25.4, "1 in"),
+            (_, false) => (100.0, "100 mm"),
+            (_, true) => (25.0, "25 mm"),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "full" => Some(PaperSize::Full),
+            "a4" => Some(PaperSize::A4),
+            "letter" => Some(PaperSize::Letter),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for PaperSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaperSize::Full => write!(f, "Single page"),
+            PaperSize::A4 => write!(f, "Tiled A4"),
+            PaperSize::Letter => write!(f, "Tiled Letter"),
+        }
+    }
+}
+
+// Grid of printer pages covering the layout. Rows are counted from the top
+struct Tiling {
+    paper: (f32, f32),
+    rows: usize,
+    columns: usize,
+    layout_height: f32,
+}
+
+impl Tiling {
+    fn new(layout: &PatternLayout, paper: (f32, f32)) -> Self {
+        let (step_x, step_y) = Self::step(paper);
+        let (width, height) = (layout.width as f32 * 1000.0, layout.height as f32 * 1000.0);
+        let count = |length: f32, step: f32| (((length - PAGE_OVERLAP) / step).ceil() as usize).max(1);
+
+        Self { paper, rows: count(height, step_y), columns: count(width, step_x), layout_height: height }
+    }
+
+    // Area of a page used for the pattern. Starts at (PAGE_MARGIN, PAGE_MARGIN + PAGE_FOOTER)
+    fn printable(paper: (f32, f32)) -> (f32, f32) {
+        (paper.0 - 2.0 * PAGE_MARGIN, paper.1 - 2.0 * PAGE_MARGIN - PAGE_FOOTER)
+    }
+
+    // Distance between neighbouring pages in the layout
+    fn step(paper: (f32, f32)) -> (f32, f32) {
+        let printable = Self::printable(paper);
+        (printable.0 - PAGE_OVERLAP, printable.1 - PAGE_OVERLAP)
+    }
+
+    // Bottom left corner of the printable area of a page, in layout coordinates (mm)
+    fn origin(&self, row: usize, column: usize) -> (f32, f32) {
+        let (step_x, step_y) = Self::step(self.paper);
+        let printable = Self::printable(self.paper);
+        let top = self.layout_height.max(printable.1) - row as f32 * step_y;
+        (column as f32 * step_x, top - printable.1)
+    }
+
+    // Page name, e.g. "B3" for the second row and third column
+    fn name(row: usize, column: usize) -> String {
+        let mut letters = String::new();
+        let mut idx = row;
+        loop {
+            letters.insert(0, (b'A' + (idx % 26) as u8) as char);
+            if idx < 26 {
+                break;
+            }
+            idx = idx / 26 - 1;
+        }
+        format!("{}{}", letters, column + 1)
+    }
+
+    // Centers of the crosses used to line up neighbouring pages. They lie in the overlapping areas
+    fn alignment_crosses(&self) -> Vec<(f32, f32)> {
+        let printable = Self::printable(self.paper);
+        let mut crosses = vec![];
+
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let (x0, y0) = self.origin(row, column);
+                if column + 1 < self.columns {
+                    // Strip shared with the page to the right
+                    let x = x0 + printable.0 - PAGE_OVERLAP * 0.5;
+                    crosses.push((x, y0 + printable.1 * 0.25));
+                    crosses.push((x, y0 + printable.1 * 0.75));
+                }
+                if row + 1 < self.rows {
+                    // Strip shared with the page below
+                    let y = y0 + PAGE_OVERLAP * 0.5;
+                    crosses.push((x0 + printable.0 * 0.25, y));
+                    crosses.push((x0 + printable.0 * 0.75, y));
+                }
+            }
+        }
+
+        crosses
+    }
+}
+
+fn mm_point(x: f32, y: f32) -> Point {
+    Point::new(Mm(x), Mm(y))
+}
+
+fn mm_line(points: Vec<(f32, f32)>, is_closed: bool) -> Line {
+    Line { points: points.into_iter().map(|(x, y)| (mm_point(x, y), false)).collect(), is_closed }
+}
+
+// Draws the cut and stitch lines of the layout, transformed from layout coordinates (m) to page coordinates (mm)
+fn draw_pieces(layer: &PdfLayerReference, layout: &PatternLayout, transform: impl Fn(&Vector2<f64>) -> (f32, f32)) {
+    for contour in layout.pieces.iter().flat_map(|placed| placed.cut.iter().chain(placed.stitch.iter())) {
+        layer.add_line(mm_line(contour.iter().map(&transform).collect(), true));
+    }
+}
+
+fn draw_cross(layer: &PdfLayerReference, center: (f32, f32)) {
+    let half = ALIGNMENT_CROSS_SIZE * 0.5;
+    layer.add_line(mm_line(vec![(center.0 - half, center.1), (center.0 + half, center.1)], false));
+    layer.add_line(mm_line(vec![(center.0, center.1 - half), (center.0, center.1 + half)], false));
+}
+
+// Only draw inside a rectangle until the graphics state is restored
+fn clip_to(layer: &PdfLayerReference, min: (f32, f32), max: (f32, f32)) {
+    layer.add_polygon(Polygon {
+        rings: vec![vec![(mm_point(min.0, min.1), false), (mm_point(max.0, min.1), false), (mm_point(max.0, max.1), false), (mm_point(min.0, max.1), false)]],
+        mode: PolygonMode::Clip,
+        winding_order: WindingOrder::NonZero,
+    });
+    layer.add_operation(Operation::new("n", vec![])); // End the clipping path without drawing it
+}
+
+// Square of known size for checking the print scale, with its bottom left corner at `corner`
+fn draw_calibration_square(layer: &PdfLayerReference, font: &IndirectFontRef, corner: (f32, f32), (size, label): (f32, &str)) {
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    layer.set_outline_thickness(0.5);
+    layer.add_line(mm_line(vec![corner, (corner.0 + size, corner.1), (corner.0 + size, corner.1 + size), (corner.0, corner.1 + size)], true));
+    layer.use_text(format!("{} x {}", label, label), 8.0, Mm(corner.0 + 2.0), Mm(corner.1 + 2.0), font);
+}
+
+// Lines of text going down from `top_left`. The first line is the title
+fn draw_title_block(layer: &PdfLayerReference, font: &IndirectFontRef, top_left: (f32, f32), lines: &[String]) {
+    for (idx, line) in lines.iter().enumerate() {
+        let size = if idx == 0 { 14.0 } else { 9.0 };
+        let y = top_left.1 - 5.0 - idx as f32 * LINE_SPACING - if idx > 0 { 2.0 } else { 0.0 };
+        layer.use_text(line.as_str(), size, Mm(top_left.0), Mm(y), font);
+    }
+}
+
+fn set_pattern_style(layer: &PdfLayerReference) {
+    let fill_color = Color::Cmyk(printpdf::Cmyk::new(0.0, 0.23, 0.0, 0.0, None));
+    let outline_color = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
+
+    layer.set_fill_color(fill_color);
+    layer.set_outline_color(outline_color);
+    layer.set_outline_thickness(2.0);
+}
+
+// Splits text into lines of at most `max_chars` characters, at spaces where possible
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let indent: String = text.chars().take_while(|c| *c == ' ').collect();
+    let mut lines = vec![];
+    let mut line = indent.clone();
+
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        if !line.trim().is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::replace(&mut line, indent.clone()));
+        }
+        // Words longer than a line are split
+        while indent.len() + word.chars().count() > max_chars {
+            let split = word.char_indices().nth(max_chars.saturating_sub(indent.len()).max(1)).map_or(word.len(), |(idx, _)| idx);
+            lines.push(format!("{}{}", indent, &word[..split]));
+            word = word[split..].to_string();
+        }
+        if !line.trim().is_empty() {
+            line.push(' ');
+        }
+        line += &word;
+    }
+    lines.push(line);
+
+    lines
+}
+
+// Lays out the headed sections of the build sheet on pages. Each line is (font size, text)
+fn paginate_build_sheet(build_sheet: &[(String, Vec<String>)], paper: (f32, f32)) -> Vec<Vec<(f32, String)>> {
+    let max_chars = ((paper.0 - 2.0 * PAGE_MARGIN) * CHARS_PER_MM) as usize;
+    let lines_per_page = ((paper.1 - 2.0 * PAGE_MARGIN) / LINE_SPACING) as usize;
+
+    let mut pages = vec![];
+    let mut page: Vec<(f32, String)> = vec![];
+
+    for (heading, lines) in build_sheet {
+        if lines.is_empty() {
+            continue;
+        }
+        // Keep headings together with their first line
+        if page.len() + 3 > lines_per_page {
+            pages.push(std::mem::take(&mut page));
+        }
+        if !page.is_empty() {
+            page.push((9.0, String::new()));
+        }
+        page.push((12.0, heading.clone()));
+
+        for line in lines.iter().flat_map(|line| wrap(line, max_chars)) {
+            if page.len() >= lines_per_page {
+                pages.push(std::mem::take(&mut page));
+            }
+            page.push((9.0, line));
+        }
+    }
+    if !page.is_empty() {
+        pages.push(page);
+    }
+
+    pages
+}
+
+fn draw_build_sheet_page(layer: &PdfLayerReference, font: &IndirectFontRef, paper: (f32, f32), lines: &[(f32, String)]) {
+    for (idx, (size, text)) in lines.iter().enumerate() {
+        layer.use_text(text.as_str(), *size, Mm(PAGE_MARGIN), Mm(paper.1 - PAGE_MARGIN - 5.0 - idx as f32 * LINE_SPACING), font);
+    }
+}
+
+// Creates the PDF document for a pattern layout, followed by the build sheet pages.
+// The first line of the title block is used as document title
+pub(crate) fn write_pattern(layout: &PatternLayout, paper_size: PaperSize, title_block: &[String], build_sheet: &[(String, Vec<String>)]) -> Result<PdfDocumentReference, OpenChuteError> {
+    let title = title_block.first().map_or("Pattern", |line| line.as_str());
+
+    // Single page output uses A4 for the build sheet
+    let sheet_paper = paper_size.dimensions().unwrap_or(PaperSize::A4.dimensions().unwrap());
+    let sheet_pages = paginate_build_sheet(build_sheet, sheet_paper);
+
+    let Some(paper) = paper_size.dimensions() else {
+        // Single page with the whole layout above the title block
+        let width = (layout.width as f32 * 1000.0).max(TITLE_BLOCK_MIN_WIDTH);
+        let (doc, page, layer) = PdfDocument::new(title, Mm(width), Mm(layout.height as f32 * 1000.0 + TITLE_BLOCK_HEIGHT), "Layer 1");
+        let layer = doc.get_page(page).get_layer(layer);
+        let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+
+        let square = paper_size.calibration_square(false);
+        draw_calibration_square(&layer, &font, (PAGE_MARGIN, PAGE_MARGIN), square);
+        draw_title_block(&layer, &font, (2.0 * PAGE_MARGIN + square.0, TITLE_BLOCK_HEIGHT - PAGE_MARGIN), title_block);
+
+        set_pattern_style(&layer);
+        draw_pieces(&layer, layout, |pt| (pt.x as f32 * 1000.0, pt.y as f32 * 1000.0 + TITLE_BLOCK_HEIGHT));
+
+        add_build_sheet(&doc, &font, sheet_paper, &sheet_pages);
+
+        return Ok(doc);
+    };
+
+    let tiling = Tiling::new(layout, paper);
+    let (doc, index_page, index_layer) = PdfDocument::new(title, Mm(paper.0), Mm(paper.1), "Index");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+
+    let index_lines = index_title_block(title_block, &tiling);
+    let index_layer = doc.get_page(index_page).get_layer(index_layer);
+    draw_index_page(&index_layer, &font, paper, paper_size, &index_lines);
+
+    // A long title block leaves no space for the overview, it then gets its own page
+    let overview = overview_area(paper, paper_size, title_block_height(index_lines.len()));
+    let index_pages = if overview.is_some() { 1 } else { 2 };
+    match overview {
+        Some(area) => draw_overview(&index_layer, &font, layout, &tiling, area),
+        None => {
+            let (page, layer) = doc.add_page(Mm(paper.0), Mm(paper.1), "Overview");
+            let area = overview_area(paper, paper_size, PAGE_MARGIN).expect("Paper is large enough for the overview");
+            draw_overview(&doc.get_page(page).get_layer(layer), &font, layout, &tiling, area);
+        }
+    }
+
+    let printable = Tiling::printable(paper);
+    let crosses = tiling.alignment_crosses();
+    let num_pages = index_pages + tiling.rows * tiling.columns + sheet_pages.len();
+
+    for row in 0..tiling.rows {
+        for column in 0..tiling.columns {
+            let (page, layer) = doc.add_page(Mm(paper.0), Mm(paper.1), "Pattern");
+            let layer = doc.get_page(page).get_layer(layer);
+            let (x0, y0) = tiling.origin(row, column);
+
+            // Layout to page, both in mm
+            let bottom = PAGE_MARGIN + PAGE_FOOTER;
+            let to_page = |x: f32, y: f32| (x - x0 + PAGE_MARGIN, y - y0 + bottom);
+
+            layer.save_graphics_state();
+            clip_to(&layer, (PAGE_MARGIN, bottom), (PAGE_MARGIN + printable.0, bottom + printable.1));
+
+            set_pattern_style(&layer);
+            draw_pieces(&layer, layout, |pt| to_page(pt.x as f32 * 1000.0, pt.y as f32 * 1000.0));
+
+            layer.set_outline_thickness(0.5);
+            for &(x, y) in &crosses {
+                draw_cross(&layer, to_page(x, y));
+            }
+
+            // Dashed lines show where the neighbouring pages overlap
+            layer.set_line_dash_pattern(LineDashPattern { dash_1: Some(3), gap_1: Some(3), ..Default::default() });
+            if column + 1 < tiling.columns {
+                let x = PAGE_MARGIN + printable.0 - PAGE_OVERLAP;
+                layer.add_line(mm_line(vec![(x, bottom), (x, bottom + printable.1)], false));
+            }
+            if row + 1 < tiling.rows {
+                let y = bottom + PAGE_OVERLAP;
+                layer.add_line(mm_line(vec![(PAGE_MARGIN, y), (PAGE_MARGIN + printable.0, y)], false));
+            }
+            layer.set_line_dash_pattern(LineDashPattern::default());
+
+            layer.restore_graphics_state();
+
+            // Check the scale of every page, printers may shrink single pages
+            let square = paper_size.calibration_square(true);
+            draw_calibration_square(&layer, &font, (PAGE_MARGIN, PAGE_MARGIN + (PAGE_FOOTER - square.0) * 0.5), square);
+            layer.use_text(title, 9.0, Mm(2.0 * PAGE_MARGIN + square.0), Mm(PAGE_MARGIN + PAGE_FOOTER * 0.5), &font);
+
+            // Labels in the margins. Neighbours are named on the side they attach to
+            let page_number = index_pages + 1 + row * tiling.columns + column; // After the index pages
+            layer.use_text(format!("{}   page {} of {}", Tiling::name(row, column), page_number, num_pages), 9.0, Mm(PAGE_MARGIN), Mm(paper.1 - PAGE_MARGIN + 3.0), &font);
+            if row > 0 {
+                layer.use_text(format!("^ {}", Tiling::name(row - 1, column)), 8.0, Mm(paper.0 * 0.5), Mm(paper.1 - PAGE_MARGIN + 3.0), &font);
+            }
+            if row + 1 < tiling.rows {
+                layer.use_text(format!("v {}", Tiling::name(row + 1, column)), 8.0, Mm(paper.0 * 0.5), Mm(bottom - 4.0), &font);
+            }
+            if column > 0 {
+                layer.use_text(format!("< {}", Tiling::name(row, column - 1)), 8.0, Mm(2.0), Mm(paper.1 * 0.5), &font);
+            }
+            if column + 1 < tiling.columns {
+                layer.use_text(format!("{} >", Tiling::name(row, column + 1)), 8.0, Mm(paper.0 - PAGE_MARGIN + 1.0), Mm(paper.1 * 0.5), &font);
+            }
+        }
+    }
+
+    add_build_sheet(&doc, &font, paper, &sheet_pages);
+
+    Ok(doc)
+}
+
+fn add_build_sheet(doc: &PdfDocumentReference, font: &IndirectFontRef, paper: (f32, f32), pages: &[Vec<(f32, String)>]) {
+    for lines in pages {
+        let (page, layer) = doc.add_page(Mm(paper.0), Mm(paper.1), "Build sheet");
+        draw_build_sheet_page(&doc.get_page(page).get_layer(layer), font, paper, lines);
+    }
+}
+
+// Title block of the index page, with instructions for assembling the pages
+fn index_title_block(title_block: &[String], tiling: &Tiling) -> Vec<String> {
+    let mut lines = title_block.to_vec();
+    lines.push(format!("{} rows x {} columns. Print at 100% scale, check the square, trim along the dashed lines and align the crosses", tiling.rows, tiling.columns));
+    lines
+}
+
+// Height from the top of the page taken by a title block with this many lines
+fn title_block_height(num_lines: usize) -> f32 {
+    PAGE_MARGIN + 10.0 + num_lines as f32 * LINE_SPACING
+}
+
+// Bottom left corner and size of the space for the overview, between `header_height` from the top
+// of the page and the calibration square. None if less than the minimum height is left
+fn overview_area(paper: (f32, f32), paper_size: PaperSize, header_height: f32) -> Option<((f32, f32), (f32, f32))> {
+    let bottom = 2.0 * PAGE_MARGIN + paper_size.calibration_square(false).0;
+    let size = (paper.0 - 2.0 * PAGE_MARGIN, paper.1 - header_height - bottom);
+    (size.1 >= MIN_OVERVIEW_HEIGHT).then_some(((PAGE_MARGIN, bottom), size))
+}
+
+// Title block and calibration square of the first page
+fn draw_index_page(layer: &PdfLayerReference, font: &IndirectFontRef, paper: (f32, f32), paper_size: PaperSize, lines: &[String]) {
+    draw_title_block(layer, font, (PAGE_MARGIN, paper.1 - PAGE_MARGIN), lines);
+    draw_calibration_square(layer, font, (PAGE_MARGIN, PAGE_MARGIN), paper_size.calibration_square(false));
+}
+
+// Overview of the whole layout and the page grid, fitted into `area`
+fn draw_overview(layer: &PdfLayerReference, font: &IndirectFontRef, layout: &PatternLayout, tiling: &Tiling, (corner, available): ((f32, f32), (f32, f32))) {
+    let printable = Tiling::printable(tiling.paper);
+
+    // Area covered by all pages
+    let (x_min, y_max) = (0.0, tiling.origin(0, 0).1 + printable.1);
+    let (x_max, y_min) = (tiling.origin(0, tiling.columns - 1).0 + printable.0, tiling.origin(tiling.rows - 1, 0).1);
+
+    let scale = (available.0 / (x_max - x_min)).min(available.1 / (y_max - y_min)).max(0.0);
+    let to_index = |x: f32, y: f32| (corner.0 + (x - x_min) * scale, corner.1 + (y - y_min) * scale);
+
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    layer.set_outline_thickness(0.5);
+    draw_pieces(layer, layout, |pt| to_index(pt.x as f32 * 1000.0, pt.y as f32 * 1000.0));
+
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.6, 0.6, 0.6, None)));
+    for row in 0..tiling.rows {
+        for column in 0..tiling.columns {
+            let (x0, y0) = tiling.origin(row, column);
+            let corners = [to_index(x0, y0), to_index(x0 + printable.0, y0), to_index(x0 + printable.0, y0 + printable.1), to_index(x0, y0 + printable.1)];
+            layer.add_line(mm_line(corners.to_vec(), true));
+            layer.use_text(Tiling::name(row, column), 8.0, Mm(corners[3].0 + 1.0), Mm(corners[3].1 - 4.0), font);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tiling, PaperSize, PAGE_OVERLAP, wrap, paginate_build_sheet, index_title_block, title_block_height, overview_area, write_pattern};
+    use crate::parachute::PatternLayout;
+
+    #[test]
+    fn test_tiling() {
+        let layout = PatternLayout { pieces: vec![], width: 0.5, height: 0.3 };
+        let tiling = Tiling::new(&layout, (210.0, 297.0));
+        assert_eq!((tiling.rows, tiling.columns), (2, 3));
+
+        // Pages cover the layout and overlap their neighbours
+        let printable = Tiling::printable((210.0, 297.0));
+        let (x_last, y_last) = tiling.origin(1, 2);
+        assert!(x_last + printable.0 >= 500.0 && y_last <= 0.0);
+        assert!((tiling.origin(0, 0).1 + PAGE_OVERLAP - (tiling.origin(1, 0).1 + printable.1)).abs() < 1e-3);
+        assert!((tiling.origin(0, 0).1 + printable.1 - 300.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_page_names() {
+        assert_eq!(Tiling::name(0, 0), "A1");
+        assert_eq!(Tiling::name(1, 2), "B3");
+        assert_eq!(Tiling::name(26, 0), "AA1");
+    }
+
+    #[test]
+    fn test_calibration_square() {
+        // Small square fits in the footer of tiled pages
+        for paper in [PaperSize::A4, PaperSize::Letter] {
+            assert!(paper.calibration_square(true).0 < super::PAGE_FOOTER);
+        }
+        assert_eq!(PaperSize::Letter.calibration_square(false).0, 4.0 * 25.4);
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("Cut out fabric", 80), vec!["Cut out fabric"]);
+        assert_eq!(wrap("aaa bbb ccc", 7), vec!["aaa bbb", "ccc"]);
+        // Indentation is kept on continued lines
+        assert_eq!(wrap("  aaa bbb", 6), vec!["  aaa", "  bbb"]);
+        assert_eq!(wrap("abcdefgh", 3), vec!["abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn test_build_sheet_pages() {
+        let steps: Vec<String> = (0..100).map(|idx| format!("{}. Step", idx + 1)).collect();
+        let pages = paginate_build_sheet(&[("Instructions".into(), steps), ("Empty".into(), vec![])], (210.0, 297.0));
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0][0].1, "Instructions");
+        assert_eq!(pages.iter().map(|page| page.len()).sum::<usize>(), 101);
+    }
+
+    #[test]
+    fn test_long_title_block() {
+        let layout = PatternLayout { pieces: vec![], width: 0.5, height: 0.3 };
+        let tiling = Tiling::new(&layout, (210.0, 297.0));
+
+        let short = index_title_block(&["Parachute".into()], &tiling);
+        assert!(overview_area((210.0, 297.0), PaperSize::A4, title_block_height(short.len())).is_some());
+
+        // The overview moves to its own page instead of getting a negative scale
+        let title_block: Vec<String> = (0..50).map(|idx| format!("Line {}", idx)).collect();
+        let long = index_title_block(&title_block, &tiling);
+        assert!(overview_area((210.0, 297.0), PaperSize::A4, title_block_height(long.len())).is_none());
+        let ((_, bottom), (width, height)) = overview_area((210.0, 297.0), PaperSize::A4, super::PAGE_MARGIN).unwrap();
+        assert!(width > 0.0 && height > 0.0 && bottom + height <= 297.0);
+
+        let bytes = write_pattern(&layout, PaperSize::A4, &title_block, &[]).unwrap().save_to_bytes().unwrap();
+        let doc = printpdf::lopdf::Document::load_mem(&bytes).unwrap();
+        assert_eq!(doc.get_pages().len(), 2 + 6);
+    }
+}
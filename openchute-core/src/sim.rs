@@ -0,0 +1,868 @@
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::io::LineWriter;
+use std::f64::consts::PI;
+
+use nalgebra::Vector2;
+use serde::{Serialize, Deserialize};
+
+use crate::error::OpenChuteError;
+
+// International Standard Atmosphere at a given height
+#[derive(Clone)]
+pub struct AtmosphericCondition {
+    pub pressure: f64, // Pa
+    pub density: f64, // kg/m3
+    pub temperature: f64, // K
+    pub sound_speed: f64, // m/s
+}
+
+
+const LAYERS: [(f64, f64); 8] = [(0.0, -0.0065), // ground
+                                (11000.0, -0.0065), // Troposphere
+                                (20000.0, 0.0), // Tropopause
+                                (32000.0, 0.001), // Stratosphere
+                                (47000.0, 0.0028), // Stratosphere
+                                (51000.0, 0.0), // Stratopause
+                                (71000.0, -0.0028), // Mesosphere
+                                (86000.0, -0.0020)];  // Mesosphere
+
+pub fn get_atmosphere(height: f64) -> AtmosphericCondition {
+	let g0 = 9.80665; // m/s^2
+    let t0 = 288.15;
+    let p0 = 101325.0;
+	let r = 287.05; // J/kgK
+	let mut t = t0; // K
+	let mut p = p0; // Pa
+
+    if height > LAYERS.last().unwrap().0 {
+        return AtmosphericCondition {
+            density: 0.0,
+            pressure: 0.0,
+            temperature: 186.946,
+            sound_speed: (1.4 * r * 186.946f64).sqrt(),
+        }
+    }
+    let h = height;
+
+    for i in 1..LAYERS.len() {
+        let layer = LAYERS[i];
+        let alpha = layer.1;
+        let mut delta_h = layer.0 - LAYERS[i-1].0;
+        if h <= layer.0 {
+            // final layer
+            delta_h = h - LAYERS[i-1].0;
+        }
+
+        let last_t = t;
+        t += alpha * delta_h;
+        if alpha == 0.0 {
+            p *= (-g0 / (r*t) * delta_h).exp();
+        } else {
+            p *= (t/last_t).powf(-g0/(alpha * r));
+        }
+
+        if h <= layer.0 {
+            break
+        }
+    }
+
+    // Speed of sound
+    let a = (1.4 * r * t).sqrt();
+    let density = p / (r * t);
+
+    AtmosphericCondition {
+        density: density,
+        pressure: p,
+        temperature: t,
+        sound_speed: a,
+    }
+}
+
+// Air density at an altitude. A measured temperature (K) is used with the standard pressure, otherwise the standard atmosphere
+pub fn get_density(altitude: f64, temperature: Option<f64>) -> f64 {
+    let atmo = get_atmosphere(altitude);
+    temperature.map_or(atmo.density, |temperature| atmo.pressure / (287.05 * temperature))
+}
+
+// Drag area CdS (m2) at which a mass (kg) descends steadily at `descent_rate` (m/s)
+pub fn drag_area_from_descent_rate(mass: f64, descent_rate: f64, density: f64) -> f64 {
+    2.0 * mass * 9.80665 / (density * descent_rate * descent_rate)
+}
+
+// Knacke lists fill constants around 8 for solid flat circular canopies, higher for slotted and ribbon canopies
+pub const DEFAULT_FILL_CONSTANT: f64 = 8.0;
+// Drag area grows with the square of the filled fraction, between the linear growth of porous canopies and the late opening of solid ones
+pub const DEFAULT_AREA_GROWTH_EXPONENT: f64 = 2.0;
+
+// When a stage deploys, or when a reefed canopy opens fully
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Trigger {
+    Altitude(f64), // Descending below this altitude (m)
+    Time(f64), // Seconds after the previous event, see Stage and Reefing
+    Velocity(f64), // Descending slower than this (m/s), e.g. once a drogue has slowed the payload down
+}
+
+impl Trigger {
+    fn is_met(&self, altitude: f64, velocity: f64, time_since_event: f64) -> bool {
+        match *self {
+            Trigger::Altitude(trigger_altitude) => altitude <= trigger_altitude && velocity <= 0.0,
+            Trigger::Time(delay) => time_since_event >= delay,
+            Trigger::Velocity(speed) => -velocity <= speed,
+        }
+    }
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Trigger::Altitude(_) => write!(f, "Below altitude"),
+            Trigger::Time(_) => write!(f, "After time"),
+            Trigger::Velocity(_) => write!(f, "Below descent rate"),
+        }
+    }
+}
+
+// Reefing holds the canopy partly closed until the disreef trigger
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Reefing {
+    pub drag_area_ratio: f64, // Reefed / full CdS
+    pub disreef: Trigger, // Time counts from the deployment of the stage
+}
+
+// One canopy of a recovery sequence. Canopies stay attached when the next one deploys, so their drag areas add up
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stage {
+    pub name: String,
+    pub area: Option<f64>, // Canopy area (m2). None uses the canopy area of the design
+    pub cd: f64, // Drag coefficient based on the canopy area
+    pub trigger: Trigger, // Checked once the previous stage has inflated. Time counts from the deployment of the previous stage
+    pub fill_constant: f64, // Canopy fill constant n. 0 opens instantly
+    pub area_growth_exponent: f64,
+    pub reefing: Option<Reefing>,
+}
+
+impl Stage {
+    pub fn new(name: &str, area: Option<f64>, cd: f64, trigger: Trigger) -> Self {
+        Self {
+            name: name.to_owned(),
+            area,
+            cd,
+            trigger,
+            fill_constant: DEFAULT_FILL_CONSTANT,
+            area_growth_exponent: DEFAULT_AREA_GROWTH_EXPONENT,
+            reefing: None,
+        }
+    }
+}
+
+// Wind at one altitude of a wind profile
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindLayer {
+    pub altitude: f64, // m
+    pub speed: f64, // m/s
+    pub direction: f64, // Direction the wind blows from, degrees clockwise from north
+}
+
+impl WindLayer {
+    // Velocity of the air towards the east and north (m/s)
+    pub fn velocity(&self) -> Vector2<f64> {
+        let direction = self.direction.to_radians();
+        -self.speed * Vector2::new(direction.sin(), direction.cos())
+    }
+}
+
+// Wind by altitude, interpolated between the layers and constant above and below them
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WindProfile {
+    pub layers: Vec<WindLayer>,
+    pub csv_path: String,
+    #[serde(skip)]
+    pub import_error: Option<String>,
+}
+
+// Compares the layers, not where they were imported from
+impl PartialEq for WindProfile {
+    fn eq(&self, other: &Self) -> bool {
+        self.layers == other.layers
+    }
+}
+
+impl WindProfile {
+    // Velocity of the air towards the east and north (m/s)
+    pub fn wind_at(&self, altitude: f64) -> Vector2<f64> {
+        let below = self.layers.iter().filter(|layer| layer.altitude <= altitude).max_by(|a, b| a.altitude.total_cmp(&b.altitude));
+        let above = self.layers.iter().filter(|layer| layer.altitude > altitude).min_by(|a, b| a.altitude.total_cmp(&b.altitude));
+
+        // Interpolate the velocities, so directions around north don't wrap
+        match (below, above) {
+            (Some(below), Some(above)) => {
+                let t = (altitude - below.altitude) / (above.altitude - below.altitude);
+                below.velocity() * (1.0 - t) + above.velocity() * t
+            },
+            (Some(layer), None) | (None, Some(layer)) => layer.velocity(),
+            (None, None) => Vector2::zeros(),
+        }
+    }
+
+    // Reads the layers from `csv_path`. Layers are only replaced if the import succeeds
+    pub fn import(&mut self) -> Result<(), OpenChuteError> {
+        self.layers = parse_wind_csv(&fs::read_to_string(&self.csv_path)?)?;
+        Ok(())
+    }
+}
+
+// Altitude (m), wind speed (m/s) and direction (deg) columns, separated by commas, semicolons or tabs.
+// Lines that don't start with a number (headers, comments) are skipped
+pub fn parse_wind_csv(text: &str) -> Result<Vec<WindLayer>, OpenChuteError> {
+    let mut layers = vec![];
+
+    for (idx, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split([',', ';', '\t']).map(str::trim).collect();
+        if fields[0].parse::<f64>().is_err() {
+            continue;
+        }
+
+        let values = fields.iter().take(3).map(|field| field.parse::<f64>()).collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| OpenChuteError::InvalidImport(format!("Line {} of the wind profile is not a number", idx + 1)))?;
+        if values.len() < 3 {
+            return Err(OpenChuteError::InvalidImport(format!("Line {} of the wind profile needs an altitude, speed and direction", idx + 1)));
+        }
+        layers.push(WindLayer { altitude: values[0], speed: values[1], direction: values[2] });
+    }
+
+    if layers.is_empty() {
+        return Err(OpenChuteError::InvalidImport("CSV file contains no wind layers".into()));
+    }
+    layers.sort_by(|a, b| a.altitude.total_cmp(&b.altitude));
+    Ok(layers)
+}
+
+// Standard deviations of the inputs varied for the landing dispersion
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Uncertainty {
+    pub runs: u16,
+    pub cd: f64, // Relative to the nominal value, e.g. 0.1 for 10 %. Varied for each stage
+    pub mass: f64, // Relative
+    pub wind_speed: f64, // Relative, scales the whole wind profile
+    pub wind_direction: f64, // deg, turns the whole wind profile
+}
+
+impl Default for Uncertainty {
+    fn default() -> Self {
+        Self { runs: 100, cd: 0.1, mass: 0.05, wind_speed: 0.2, wind_direction: 15.0 }
+    }
+}
+
+// Descent inputs that are stored with the design
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimSettings {
+    pub payload_mass: f64, // kg
+    pub altitude: f64, // Altitude at the start of the simulation (m)
+    pub speed: f64, // Downward speed at the start (m/s)
+    pub stages: Vec<Stage>, // Deployed in order
+    pub wind: Option<WindProfile>, // None simulates a vertical descent
+    pub uncertainty: Uncertainty,
+}
+
+impl Default for SimSettings {
+    fn default() -> Self {
+        Self {
+            payload_mass: 1.0,
+            altitude: 300.0,
+            speed: 0.0,
+            stages: vec![Stage::new("Main", None, 0.8, Trigger::Time(0.0))],
+            wind: None,
+            uncertainty: Uncertainty::default(),
+        }
+    }
+}
+
+// Loads of one stage
+pub struct StageResult {
+    pub name: String,
+    pub deployment_time: Option<f64>, // s, None if it lands first
+    pub opening_time: Option<f64>, // Time from deployment to full (reefed) inflation (s)
+    pub peak_force: f64, // Highest force on this canopy (N)
+    pub load_factor: Option<f64>, // Peak force / (dynamic pressure at deployment * full drag area). None when deployed at zero speed
+    pub disreef_time: Option<f64>, // s, None if not reefed or it lands first
+    pub disreef_force: Option<f64>, // Highest force after disreefing (N)
+}
+
+#[derive(Clone)]
+pub struct SimData {
+    pub time: f64, // s
+    pub altitude: f64, // m
+    pub velocity: f64, // m/s, positive upwards
+    pub acceleration: f64, // m/s2
+    pub position: Vector2<f64>, // East and north of the start (m)
+    pub horizontal_velocity: Vector2<f64>, // Towards the east and north (m/s)
+    pub force: f64, // Drag force of all stages (N)
+    pub stage_forces: Vec<f64>, // N
+    pub drag_area: f64, // CdS of all stages (m2), grows while canopies inflate
+    pub atmosphere: AtmosphericCondition,
+    pub dynamic_pressure: f64, // Pa
+    pub mach: f64,
+    pub stagnation_temp: f64, // K
+}
+
+#[derive(Clone, Copy)]
+struct Event {
+    time: f64,
+    distance: f64, // Travelled since the start (m)
+}
+
+// Progress of a stage during the simulation
+#[derive(Clone, Copy, Default)]
+struct StageState {
+    deployed: Option<Event>,
+    deployment_pressure: f64, // Pa
+    inflated: Option<f64>, // Time of the first full (reefed) inflation
+    disreefed: Option<Event>,
+    peak_force: f64,
+    peak_disreef_force: f64,
+}
+
+// Canopies inflate over a fixed distance of n nominal diameters (Knacke), i.e. the filling time is n * D0 / v.
+// While filling, CdS = CdS_full * (filled fraction)^area_growth_exponent.
+// Drag acts against the velocity through the air, so the payload drifts with the wind
+pub struct Sim {
+    mass: f64, // Mass in kg
+    initial_altitude: f64, // Initial altitude in m
+    initial_speed: f64, // Initial (downward) speed
+    canopy_area: f64, // For stages without their own area (m2)
+    stages: Vec<Stage>,
+    wind: WindProfile,
+    stage_states: Vec<StageState>,
+    sim_results: Vec<SimData>,
+    sim_updated: bool, //simulation results are up to date 
+}
+
+impl Sim {
+    // Single canopy that opens instantly
+    pub fn new(mass: f64, altitude: f64, speed: f64, area: f64, cd: f64) -> Self {
+        let mut stage = Stage::new("Canopy", Some(area), cd, Trigger::Time(0.0));
+        stage.fill_constant = 0.0;
+        Self::with_stages(mass, altitude, speed, area, vec![stage])
+    }
+
+    pub fn with_stages(mass: f64, altitude: f64, speed: f64, canopy_area: f64, stages: Vec<Stage>) -> Self {
+        Self {
+            mass: mass,
+            initial_altitude: altitude,
+            initial_speed: speed,
+            canopy_area,
+            stages,
+            wind: WindProfile::default(),
+            stage_states: vec![],
+            sim_results: vec![],
+            sim_updated: false,
+        }
+    }
+
+    pub fn from_settings(settings: &SimSettings, canopy_area: f64) -> Self {
+        let mut sim = Self::with_stages(settings.payload_mass, settings.altitude, settings.speed, canopy_area, settings.stages.clone());
+        sim.wind = settings.wind.clone().unwrap_or_default();
+        sim
+    }
+
+    fn stage_area(&self, stage: &Stage) -> f64 {
+        stage.area.unwrap_or(self.canopy_area)
+    }
+
+    // Filled fraction of the current inflation (first opening or disreefing), its fill distance (m) and the drag area (m2) of a stage
+    fn stage_inflation(&self, stage: &Stage, state: &StageState, distance: f64) -> (f64, f64, f64) {
+        let Some(deployed) = state.deployed else { return (0.0, 0.0, 0.0) };
+        let area = self.stage_area(stage);
+        let nominal_diameter = (4.0 * area / PI).sqrt();
+        let reefed_ratio = stage.reefing.map_or(1.0, |reefing| reefing.drag_area_ratio.clamp(0.0, 1.0));
+
+        // The canopy fills over n times the diameter it opens up by
+        let (start, fill_diameter, from, to) = match state.disreefed {
+            None => (deployed.distance, nominal_diameter * reefed_ratio.sqrt(), 0.0, reefed_ratio),
+            Some(disreefed) => (disreefed.distance, nominal_diameter * (1.0 - reefed_ratio.sqrt()), reefed_ratio, 1.0),
+        };
+        let fill_distance = stage.fill_constant.max(0.0) * fill_diameter;
+        let filled = if fill_distance > 0.0 { ((distance - start) / fill_distance).clamp(0.0, 1.0) } else { 1.0 };
+
+        (filled, fill_distance, area * stage.cd * (from + (to - from) * filled.powf(stage.area_growth_exponent.max(0.0))))
+    }
+
+    // Deploys and disreefs the stages whose triggers are met
+    fn update_events(&self, states: &mut [StageState], now: Event, altitude: f64, velocity: f64, dynamic_pressure: f64) {
+        for (idx, stage) in self.stages.iter().enumerate() {
+            let state = states[idx];
+            match (state.deployed, stage.reefing) {
+                (None, _) => {
+                    // Stages deploy in order, each once the previous one has inflated
+                    let since = if idx == 0 { Some(0.0) } else { states[idx - 1].inflated.and(states[idx - 1].deployed).map(|event| event.time) };
+                    if since.is_some_and(|since| stage.trigger.is_met(altitude, velocity, now.time - since)) {
+                        states[idx].deployed = Some(now);
+                        states[idx].deployment_pressure = dynamic_pressure;
+                    }
+                },
+                (Some(deployed), Some(reefing)) if state.inflated.is_some() && state.disreefed.is_none()
+                    && reefing.disreef.is_met(altitude, velocity, now.time - deployed.time) => {
+                    states[idx].disreefed = Some(now);
+                },
+                _ => {},
+            }
+        }
+    }
+
+    // Full drag area of the deployed stages and the next one to deploy (m2)
+    fn active_drag_area(&self, states: &[StageState]) -> f64 {
+        let next = states.iter().position(|state| state.deployed.is_none()).unwrap_or(states.len());
+        self.stages.iter().take(next + 1).map(|stage| self.stage_area(stage) * stage.cd).sum()
+    }
+
+    // 0.5 s when the acceleration is low, 0.1 s otherwise. Light payloads with a large canopy need shorter steps
+    // so the drag doesn't overshoot within a step
+    fn timestep(&self, density: f64, velocity: f64, acceleration: f64, drag_area: f64) -> f64 {
+        let dt: f64 = if acceleration < 1.0 { 0.5 } else { 0.1 };
+        let drag_rate = density * drag_area * velocity.abs() / self.mass; // 1/s
+        dt.min(0.2 / drag_rate)
+    }
+
+    // Integrates the descent until landing. Results are cached, create a new Sim for other inputs
+    pub fn simulate(&mut self) -> Vec<SimData> {
+        if self.sim_updated {
+            return self.sim_results.clone();
+        }
+        
+        let mut altitude = self.initial_altitude;
+        let mut velocity = -self.initial_speed; // Positive upwards
+        let mut acceleration = -9.80665;
+        let mut position: Vector2<f64> = Vector2::zeros(); // East and north of the start
+        let mut horizontal_velocity: Vector2<f64> = Vector2::zeros(); // Starts without drift, e.g. at apogee
+        let mut horizontal_acceleration: Vector2<f64> = Vector2::zeros();
+        let mut time = 0.0;
+        let mut distance = 0.0; // Travelled since the start
+        let mut states = vec![StageState::default(); self.stages.len()];
+        let mut dt = self.timestep(get_atmosphere(altitude).density, velocity, acceleration, self.active_drag_area(&states)).min(0.1);
+        self.sim_results.clear();
+
+        for _ in 0..100000 {
+            let step = velocity * dt + 0.5 * acceleration * dt * dt;
+            altitude += step;
+            position += horizontal_velocity * dt + 0.5 * horizontal_acceleration * dt * dt;
+            let relative_wind = horizontal_velocity - self.wind.wind_at(altitude); // Horizontal velocity through the air
+            distance += (step * step + (relative_wind * dt).norm_squared()).sqrt();
+            time += dt;
+
+            let atmo = get_atmosphere(altitude);
+            let airspeed = (velocity * velocity + relative_wind.norm_squared()).sqrt();
+            let dynamic_pressure = 0.5 * atmo.density * airspeed * airspeed;
+            self.update_events(&mut states, Event { time, distance }, altitude, velocity, dynamic_pressure);
+
+            let mut stage_forces = vec![];
+            let mut drag_area = 0.0;
+            let mut fill_dt = f64::INFINITY; // Resolve openings with at least 20 steps
+            for (stage, state) in self.stages.iter().zip(states.iter_mut()) {
+                let (filled, fill_distance, stage_drag_area) = self.stage_inflation(stage, state, distance);
+                if state.deployed.is_some() {
+                    if filled >= 1.0 && state.inflated.is_none() {
+                        state.inflated = Some(time);
+                    }
+                    if filled < 1.0 {
+                        fill_dt = fill_dt.min(fill_distance / (20.0 * airspeed));
+                    }
+                }
+
+                // Vertical component of the drag, the peak is the full drag
+                let stage_drag = dynamic_pressure * stage_drag_area;
+                let stage_force = if airspeed > 0.0 { -stage_drag * velocity / airspeed } else { 0.0 };
+                state.peak_force = state.peak_force.max(stage_drag);
+                if state.disreefed.is_some() {
+                    state.peak_disreef_force = state.peak_disreef_force.max(stage_drag);
+                }
+                stage_forces.push(stage_force);
+                drag_area += stage_drag_area;
+            }
+            let force: f64 = stage_forces.iter().sum();
+            let horizontal_force = if airspeed > 0.0 { -dynamic_pressure * drag_area * relative_wind / airspeed } else { Vector2::zeros() };
+
+            let acceleration_old = acceleration;
+            acceleration = -9.80665 + force / self.mass;
+            let horizontal_acceleration_old = horizontal_acceleration;
+            horizontal_acceleration = horizontal_force / self.mass;
+            // Velocity verlet
+            velocity += 0.5 * (acceleration + acceleration_old) * dt;
+            horizontal_velocity += 0.5 * (horizontal_acceleration + horizontal_acceleration_old) * dt;
+
+            if altitude < 0.0 {
+                break
+            }
+
+            let airspeed = (velocity * velocity + (horizontal_velocity - self.wind.wind_at(altitude)).norm_squared()).sqrt();
+            dt = self.timestep(atmo.density, airspeed, acceleration, self.active_drag_area(&states)).min(fill_dt);
+            if states.iter().any(|state| state.deployed.is_none()) {
+                dt = dt.min(0.1); // Catch deployment triggers in time
+            }
+            let mach = velocity / atmo.sound_speed;
+            let stagnation = (1.0 + (1.4 - 1.0) / 2.0 * mach.powi(2)) * atmo.temperature;
+            self.sim_results.push(SimData {
+                acceleration: acceleration,
+                altitude: altitude,
+                force: force,
+                stage_forces,
+                drag_area,
+                time: time,
+                velocity: velocity,
+                position,
+                horizontal_velocity,
+                dynamic_pressure: 0.5 * atmo.density * airspeed * airspeed,
+                atmosphere: atmo,
+                mach: mach,
+                stagnation_temp: stagnation,
+            });
+        }
+
+        self.stage_states = states;
+        self.sim_updated = true;
+        return self.sim_results.clone();
+    }
+
+    pub fn get_stage_results(&mut self) -> Vec<StageResult> {
+        self.simulate();
+
+        self.stages.iter().zip(self.stage_states.iter()).map(|(stage, state)| {
+            let full_force = state.deployment_pressure * self.stage_area(stage) * stage.cd;
+            StageResult {
+                name: stage.name.clone(),
+                deployment_time: state.deployed.map(|event| event.time),
+                opening_time: state.deployed.zip(state.inflated).map(|(deployed, inflated)| inflated - deployed.time),
+                peak_force: state.peak_force,
+                load_factor: if full_force > 1e-9 { Some(state.peak_force / full_force) } else { None },
+                disreef_time: state.disreefed.map(|event| event.time),
+                disreef_force: state.disreefed.map(|_| state.peak_disreef_force),
+            }
+        }).collect()
+    }
+
+    // Where the descent reaches the ground, east and north of the start (m)
+    pub fn get_landing_point(&mut self) -> Option<Vector2<f64>> {
+        self.simulate();
+
+        match self.sim_results.as_slice() {
+            [.., previous, last] if last.altitude < 0.0 && previous.altitude > last.altitude => {
+                let t = previous.altitude / (previous.altitude - last.altitude);
+                Some(previous.position + (last.position - previous.position) * t)
+            },
+            [.., last] => Some(last.position),
+            [] => None,
+        }
+    }
+
+    pub fn save_data(&self, filename: &str) -> std::io::Result<()> {
+        let file = File::create(filename)?;
+        let mut file = LineWriter::new(file);
+        file.write_all(b"time[s],altitude[m],velocity[m/s],dynamic_pressure[Pa],force[N],stagnationT[K],Mach,east[m],north[m]\n")?;
+
+        for dat in self.sim_results.iter() {
+            writeln!(file, "{:.2},{:.2},{:.2},{:.1},{:.1},{:.1},{:.3},{:.2},{:.2}", dat.time, dat.altitude, dat.velocity, dat.dynamic_pressure, dat.force, dat.stagnation_temp, dat.mach, dat.position.x, dat.position.y)?;
+        }
+
+        file.flush()
+    }
+}
+
+// 95 % of the landings fall within 2.45 standard deviations (chi-squared with two degrees of freedom)
+const ELLIPSE_SCALE: f64 = 2.4477;
+
+// Spread of the landing points, with the 95 % ellipse
+#[derive(Clone)]
+pub struct Dispersion {
+    pub landings: Vec<Vector2<f64>>, // East and north of the start (m)
+    pub mean: Vector2<f64>,
+    pub semi_major: f64, // m
+    pub semi_minor: f64, // m
+    pub orientation: f64, // Direction of the major axis, degrees clockwise from north (0-180)
+}
+
+impl Dispersion {
+    fn from_landings(landings: Vec<Vector2<f64>>) -> Option<Self> {
+        if landings.len() < 2 {
+            return None;
+        }
+
+        let n = landings.len() as f64;
+        let mean = landings.iter().sum::<Vector2<f64>>() / n;
+        let (mut xx, mut xy, mut yy) = (0.0, 0.0, 0.0);
+        for landing in &landings {
+            let d = landing - mean;
+            xx += d.x * d.x / (n - 1.0);
+            xy += d.x * d.y / (n - 1.0);
+            yy += d.y * d.y / (n - 1.0);
+        }
+
+        // Eigenvalues and major axis of the 2x2 covariance matrix
+        let center = 0.5 * (xx + yy);
+        let radius = (0.25 * (xx - yy).powi(2) + xy * xy).sqrt();
+        let angle = 0.5 * (2.0 * xy).atan2(xx - yy); // From east towards north
+
+        Some(Self {
+            landings,
+            mean,
+            semi_major: ELLIPSE_SCALE * (center + radius).max(0.0).sqrt(),
+            semi_minor: ELLIPSE_SCALE * (center - radius).max(0.0).sqrt(),
+            orientation: (90.0 - angle.to_degrees()).rem_euclid(180.0),
+        })
+    }
+
+    // Outline of the 95 % ellipse
+    pub fn ellipse(&self, points: usize) -> Vec<Vector2<f64>> {
+        let orientation = self.orientation.to_radians();
+        let major = Vector2::new(orientation.sin(), orientation.cos());
+        let minor = Vector2::new(-major.y, major.x);
+
+        (0..=points).map(|idx| {
+            let phi = 2.0 * PI * idx as f64 / points as f64;
+            self.mean + major * self.semi_major * phi.cos() + minor * self.semi_minor * phi.sin()
+        }).collect()
+    }
+}
+
+// Small random number generator (SplitMix64). The fixed seed makes the same settings give the same dispersion
+struct Random(u64);
+
+impl Random {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1)
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Standard normal distribution (Box-Muller)
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+
+    // Multiplier around 1 with a relative standard deviation, kept positive
+    fn factor(&mut self, sigma: f64) -> f64 {
+        (1.0 + sigma * self.normal()).max(0.05)
+    }
+}
+
+// Monte Carlo simulation of the landing point, varying the Cd of each stage, the payload mass and the wind
+// by the uncertainties in the settings. None with fewer than two runs
+pub fn landing_dispersion(settings: &SimSettings, canopy_area: f64) -> Option<Dispersion> {
+    let uncertainty = &settings.uncertainty;
+    let mut random = Random(0x6F70656E63687574);
+
+    let mut landings = vec![];
+    for _ in 0..uncertainty.runs {
+        let mut varied = settings.clone();
+        varied.payload_mass *= random.factor(uncertainty.mass);
+        for stage in varied.stages.iter_mut() {
+            stage.cd *= random.factor(uncertainty.cd);
+        }
+        if let Some(wind) = &mut varied.wind {
+            let speed = random.factor(uncertainty.wind_speed);
+            let turn = uncertainty.wind_direction * random.normal(); // deg
+            for layer in wind.layers.iter_mut() {
+                layer.speed *= speed;
+                layer.direction += turn;
+            }
+        }
+
+        if let Some(landing) = Sim::from_settings(&varied, canopy_area).get_landing_point() {
+            landings.push(landing);
+        }
+    }
+
+    Dispersion::from_landings(landings)
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::{get_atmosphere, landing_dispersion, parse_wind_csv, Reefing, Sim, SimSettings, Stage, Trigger, WindLayer, WindProfile};
+    use nalgebra::Vector2;
+    use std::f64::consts::PI;
+
+    fn west_wind(speed: f64) -> Option<WindProfile> {
+        Some(WindProfile { layers: vec![WindLayer { altitude: 0.0, speed, direction: 270.0 }], ..Default::default() })
+    }
+
+/*
+    #[test]
+    fn test_isa() {
+        println!("DENSITY: {}", get_density(18000.0));
+    }
+ */
+    #[test]
+    fn test_sim() {
+        let mut s = Sim::new(56.0, 150000.0, 0.0, 2.24, 1.0);
+        let results = s.simulate();
+        let csv = std::env::temp_dir().join("openchute_test_sim.csv");
+        s.save_data(csv.to_str().unwrap()).unwrap();
+
+        // Lands at the terminal velocity of the sea level density
+        let landing = results.last().unwrap();
+        assert!(landing.altitude < 10.0);
+        let terminal = (2.0 * 56.0 * 9.80665 / (get_atmosphere(0.0).density * 2.24 * 1.0)).sqrt();
+        assert!((landing.velocity + terminal).abs() < 0.05 * terminal, "Landing velocity {}", landing.velocity);
+    }
+
+    #[test]
+    fn test_deployment_speed() {
+        // Deploying at speed decelerates to the same terminal velocity without bouncing back up
+        let from_rest = Sim::new(1.0, 300.0, 0.0, 1.0, 0.8).simulate();
+        let from_speed = Sim::new(1.0, 300.0, 30.0, 1.0, 0.8).simulate();
+        assert!(from_speed[0].velocity < -20.0);
+        assert!(from_speed.iter().all(|dat| dat.velocity < 0.0));
+        assert!((from_speed.last().unwrap().velocity - from_rest.last().unwrap().velocity).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_opening_shock() {
+        // Opens instantly by default, so the peak force is the drag at deployment
+        let mut instant = Sim::new(10.0, 1000.0, 50.0, 2.0, 0.8);
+        let shock = &instant.get_stage_results()[0];
+        assert!(shock.opening_time.unwrap() < 0.2);
+        assert!((shock.load_factor.unwrap() - 1.0).abs() < 0.05, "Load factor {:?}", shock.load_factor);
+
+        // Inflating over n diameters takes about n * D0 / v and lowers the peak as the payload slows down while filling
+        let mut stage = Stage::new("Main", None, 0.8, Trigger::Time(0.0));
+        stage.fill_constant = 8.0;
+        stage.area_growth_exponent = 2.0;
+        let mut inflating = Sim::with_stages(10.0, 1000.0, 50.0, 2.0, vec![stage]);
+        let shock = &inflating.get_stage_results()[0];
+        let nominal_diameter = (4.0 * 2.0 / PI).sqrt();
+        let opening_time = shock.opening_time.unwrap();
+        assert!(opening_time > 8.0 * nominal_diameter / 50.0 && opening_time < 2.0 * 8.0 * nominal_diameter / 50.0, "Opening time {}", opening_time);
+        assert!(shock.load_factor.unwrap() < 1.0);
+
+        let results = inflating.simulate();
+        assert!(results[0].drag_area < 0.01 * 2.0 * 0.8);
+        assert!((results.last().unwrap().drag_area - 2.0 * 0.8).abs() < 1e-9);
+
+        // Deployed at apogee there is no dynamic pressure to compare to
+        assert!(Sim::new(10.0, 1000.0, 0.0, 2.0, 0.8).get_stage_results()[0].load_factor.is_none());
+    }
+
+    #[test]
+    fn test_stages() {
+        // Drogue at apogee, main at 300 m
+        let drogue = Stage::new("Drogue", Some(0.1), 1.5, Trigger::Time(0.0));
+        let main = Stage::new("Main", None, 0.8, Trigger::Altitude(300.0));
+        let mut sim = Sim::with_stages(2.0, 1000.0, 0.0, 2.0, vec![drogue.clone(), main]);
+        let results = sim.simulate();
+        let stages = sim.get_stage_results();
+
+        let deployment = stages[1].deployment_time.unwrap();
+        assert!((results.iter().find(|dat| dat.time >= deployment).unwrap().altitude - 300.0).abs() < 10.0);
+
+        // Drag areas add up, the drogue stays attached
+        let terminal = (2.0 * 2.0 * 9.80665 / (get_atmosphere(0.0).density * (0.1 * 1.5 + 2.0 * 0.8))).sqrt();
+        assert!((results.last().unwrap().velocity + terminal).abs() < 0.05 * terminal);
+        assert_eq!(results.last().unwrap().stage_forces.len(), 2);
+
+        // The main opens at the drogue descent rate and takes the larger load
+        assert!(stages[1].peak_force > stages[0].peak_force);
+
+        // Main waits until the drogue has slowed the payload down
+        let main = Stage::new("Main", None, 0.8, Trigger::Velocity(20.0));
+        let mut sim = Sim::with_stages(2.0, 1000.0, 50.0, 2.0, vec![drogue, main]);
+        let results = sim.simulate();
+        let deployment = sim.get_stage_results()[1].deployment_time.unwrap();
+        assert!(results.iter().find(|dat| dat.time >= deployment).unwrap().velocity > -20.5);
+    }
+
+    #[test]
+    fn test_reefing() {
+        let mut stage = Stage::new("Main", None, 0.8, Trigger::Time(0.0));
+        stage.fill_constant = 0.0;
+        stage.reefing = Some(Reefing { drag_area_ratio: 0.25, disreef: Trigger::Time(2.0) });
+        let mut sim = Sim::with_stages(5.0, 1000.0, 20.0, 2.0, vec![stage]);
+        let results = sim.simulate();
+        let result = &sim.get_stage_results()[0];
+
+        // Reefed drag area until the disreef time after deployment, then the full drag area
+        let disreef_time = result.disreef_time.unwrap();
+        assert!((disreef_time - result.deployment_time.unwrap() - 2.0).abs() < 0.1);
+        assert!(results.iter().filter(|dat| dat.time < disreef_time).all(|dat| (dat.drag_area - 0.25 * 1.6).abs() < 1e-9));
+        assert!((results.last().unwrap().drag_area - 1.6).abs() < 1e-9);
+
+        // Disreefing at the reefed descent rate gives a second shock above the steady drag
+        assert!(result.disreef_force.unwrap() > 1.2 * 5.0 * 9.80665);
+    }
+
+    #[test]
+    fn test_wind_profile() {
+        let csv = "altitude[m],speed[m/s],direction[deg]\n# Sounding\n1000, 10, 90\n0; 4; 0\n\n";
+        let wind = WindProfile { layers: parse_wind_csv(csv).unwrap(), ..Default::default() };
+        assert_eq!(wind.layers.len(), 2);
+        assert_eq!(wind.layers[0].altitude, 0.0);
+
+        // Wind from the north blows south, from the east it blows west. Constant outside the layers
+        assert!((wind.wind_at(-10.0) - Vector2::new(0.0, -4.0)).norm() < 1e-9);
+        assert!((wind.wind_at(2000.0) - Vector2::new(-10.0, 0.0)).norm() < 1e-9);
+        assert!((wind.wind_at(500.0) - Vector2::new(-5.0, -2.0)).norm() < 1e-9);
+
+        assert!(parse_wind_csv("altitude,speed,direction\n").is_err());
+        assert!(parse_wind_csv("100, 5\n").is_err());
+        assert!(parse_wind_csv("100, 5, east\n").is_err());
+    }
+
+    #[test]
+    fn test_wind_drift() {
+        let settings = SimSettings { wind: west_wind(5.0), ..Default::default() };
+        let mut sim = Sim::from_settings(&settings, 1.0);
+        let results = sim.simulate();
+        let landing = sim.get_landing_point().unwrap();
+        let descent_time = results.last().unwrap().time;
+
+        // Drifts east with the wind once the canopy has opened
+        assert!(landing.x > 0.9 * 5.0 * descent_time && landing.x < 5.0 * descent_time, "Drift {} m in {} s", landing.x, descent_time);
+        assert!(landing.y.abs() < 1e-3);
+        assert!((results.last().unwrap().horizontal_velocity.x - 5.0).abs() < 0.05);
+
+        // Without wind the descent stays vertical
+        assert_eq!(Sim::from_settings(&SimSettings::default(), 1.0).get_landing_point(), Some(Vector2::zeros()));
+    }
+
+    #[test]
+    fn test_landing_dispersion() {
+        let mut settings = SimSettings { wind: west_wind(5.0), ..Default::default() };
+        settings.uncertainty.runs = 50;
+        let nominal = Sim::from_settings(&settings, 1.0).get_landing_point().unwrap();
+
+        let dispersion = landing_dispersion(&settings, 1.0).unwrap();
+        assert_eq!(dispersion.landings.len(), 50);
+        assert!((dispersion.mean - nominal).norm() < 0.2 * nominal.norm());
+        assert!(dispersion.semi_major >= dispersion.semi_minor && dispersion.semi_minor > 0.0);
+        assert!((0.0..180.0).contains(&dispersion.orientation));
+
+        // Repeatable for the same settings
+        let again = landing_dispersion(&settings, 1.0).unwrap();
+        assert_eq!(again.mean, dispersion.mean);
+
+        // Ellipse around the mean
+        let ellipse = dispersion.ellipse(36);
+        assert!(ellipse.iter().all(|pt| (pt - dispersion.mean).norm() <= dispersion.semi_major + 1e-9));
+
+        // No uncertainty, no spread
+        settings.uncertainty.cd = 0.0;
+        settings.uncertainty.mass = 0.0;
+        settings.uncertainty.wind_speed = 0.0;
+        settings.uncertainty.wind_direction = 0.0;
+        let exact = landing_dispersion(&settings, 1.0).unwrap();
+        assert!(exact.semi_major < 1e-6);
+        assert!((exact.mean - nominal).norm() < 1e-6);
+
+        settings.uncertainty.runs = 1;
+        assert!(landing_dispersion(&settings, 1.0).is_none());
+    }
+}
@@ -0,0 +1,164 @@
+#![allow(unused)]
+
+use std::f32::consts::PI;
+use std::ops::RangeInclusive;
+use eframe::egui::{self, Widget};
+
+use uom::si::f32::*;
+use uom::si::length;
+use uom::{si, ConversionFactor};
+
+// Slider for handling a length including unit conversions
+pub fn length_slider<S,I>(ui: &mut egui::Ui, value_m: &mut f64, use_imperial: bool, range: RangeInclusive<f64>, si_unit: &S, imperial_unit: &I) -> egui::Response
+where
+S: uom::Conversion<f64> + si::Unit,
+I: uom::Conversion<f64> + si::Unit
+{    
+    let unit_abbrev = if use_imperial { I::abbreviation() } else { S::abbreviation() };
+    let conversion_factor = if use_imperial { imperial_unit.conversion().value() } else { si_unit.conversion().value() };;
+
+
+    let mut value = *value_m / conversion_factor;
+    let new_range = (range.start() / conversion_factor).round() ..= (range.end() / conversion_factor).round();
+
+
+    let field = egui::Slider::new::<f64>(&mut value, new_range)
+                            .text(format!("[{}]", unit_abbrev))
+                            .clamp_to_range(false);
+    
+    let res = ui.add(field)
+                          .context_menu(|ui| {
+                            if ui.button("Reset value").clicked() {
+                                ui.close_menu();
+                                todo!();
+                            }
+                        });
+
+    *value_m = (value * conversion_factor).max(0.0).min(10000.0); // Absolute limits
+
+    res
+}
+
+// Slider for handling a length including unit conversions
+pub fn length_slider_no_limit<S,I>(ui: &mut egui::Ui, value_m: &mut f64, use_imperial: bool, range: RangeInclusive<f64>, si_unit: &S, imperial_unit: &I) -> egui::Response
+where
+S: uom::Conversion<f64> + si::Unit,
+I: uom::Conversion<f64> + si::Unit
+{    
+    let unit_abbrev = if use_imperial { I::abbreviation() } else { S::abbreviation() };
+    let conversion_factor = if use_imperial { imperial_unit.conversion().value() } else { si_unit.conversion().value() };;
+
+
+    let mut value = *value_m / conversion_factor;
+    let new_range = (range.start() / conversion_factor).round() ..= (range.end() / conversion_factor).round();
+
+
+    let field = egui::Slider::new::<f64>(&mut value, new_range)
+                            .text(format!("[{}]", unit_abbrev))
+                            .clamp_to_range(false);
+    
+    let res = ui.add(field)
+                          .context_menu(|ui| {
+                            if ui.button("Reset value").clicked() {
+                                ui.close_menu();
+                                todo!();
+                            }
+                        });
+
+    *value_m = value * conversion_factor;
+
+    res
+}
+
+pub fn integer_edit_field(ui: &mut egui::Ui, value: &mut u16) -> egui::Response {
+    let field = egui::Slider::new::<u16>(value, 4..=24)
+                            .text("[]")
+                            .clamp_to_range(false);
+    
+    let res = ui.add(field)
+                          .context_menu(|ui| {
+                            if ui.button("Reset value").clicked() {
+                                ui.close_menu();
+                            }
+                        });
+
+    res
+}
+
+pub fn delete_move_buttons(ui: &mut egui::Ui, to_delete: &mut Option<usize>, to_move: &mut Option<(usize, bool)>, idx: usize, num_parameters: usize) {
+    if ui.button("❌").on_hover_text("Delete").clicked() {
+        *to_delete = Some(idx);
+    }
+    if ui.add_enabled(idx != 0, egui::Button::new("⬆")).on_hover_text("Move up").clicked() {
+        *to_move = Some((idx, true));
+    }
+    if ui.add_enabled(idx < num_parameters - 1, egui::Button::new("⬇")).on_hover_text("Move down").clicked() {
+        *to_move = Some((idx, false));
+    };
+}
+
+// Linear dimension. metric=m, imperial=ft.
+// Always stored as m in backend
+pub fn dimension_field(ui: &mut egui::Ui, value_metric: &mut f64, use_imperial: bool, range: RangeInclusive<f64>) -> egui::Response {
+
+    let mut value = if use_imperial { *value_metric / 0.3048 } else { *value_metric };
+    let new_range = if use_imperial { range.start() / 0.3048 ..= (range.end() / 0.3048).round() } else { range };
+
+
+    ui.label("Diameter:").on_hover_text("Number of parachute gores. Typically between 6 and 24");
+    let field = egui::Slider::new::<f64>(&mut value, new_range)
+                            .text(if use_imperial { "[ft]" } else { "[m]" })
+                            .clamp_to_range(false);
+
+    let res = ui.add(field)
+                          .context_menu(|ui| {
+                            if ui.button("Reset value").clicked() {
+                                ui.close_menu();
+                            }
+                        });
+
+    *value_metric = (if use_imperial { value * 0.3048 } else { value }).max(0.0);
+
+    res
+}
+
+pub fn number_edit_field(ui: &mut egui::Ui, value: &mut f64) -> egui::Response {
+    let mut tmp_value = format!("{}", value);
+
+    let res = ui.add(egui::TextEdit::singleline(&mut tmp_value).desired_width(10.0).clip_text(false));
+
+    if let Ok(result) = tmp_value.parse() {
+        *value = result;
+    }
+    res
+}
+
+// Button to pick a file, with the chosen path and a button to read it again. Returns true if the file should be imported.
+// Imported data is saved with the design, so the file is only needed when (re)importing. The error of the last import is shown below
+pub fn file_import(ui: &mut egui::Ui, path: &mut String, error: &Option<String>, button: &str, hint: &str, extensions: &[&str]) -> bool {
+    let mut import = false;
+
+    ui.horizontal(|ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button(button).on_hover_text(hint).clicked() {
+            if let Some(picked) = rfd::FileDialog::new().add_filter("*", extensions).pick_file() {
+                *path = picked.to_string_lossy().into_owned();
+                import = true;
+            }
+        }
+        if !path.is_empty() {
+            ui.label(path.as_str());
+            import |= ui.button("Reimport").on_hover_text("Reads the file again, e.g. after editing it").clicked();
+        }
+    });
+
+    if let Some(error) = error {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+
+    import
+}
+
+pub fn rgb_to_srgba(rgb: &[f32; 3]) -> three_d::Srgba {
+    three_d::Srgba::new((rgb[0] * 255.0) as u8, (rgb[1] * 255.0) as u8, (rgb[2] * 255.0) as u8, 255)
+}
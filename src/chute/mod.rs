@@ -1,6 +1,3 @@
-pub mod geometry;
-pub mod parachute;
+// The design engine lives in the openchute-core crate, only the 3D preview is specific to the app
+pub use openchute_core::{geometry, parachute, sim, configurable_shapes, materials, pdf};
 pub mod ui;
-pub mod sim;
-pub mod configurable_shapes;
-pub mod materials;
\ No newline at end of file
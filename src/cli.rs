@@ -0,0 +1,215 @@
+// Headless command line interface. Used to regenerate patterns from scripts without opening a window
+// Example: openchute export ringsail.chute --dxf out.dxf --pdf out.pdf --set diameter=2.4
+
+use std::path::PathBuf;
+
+use evalexpr::ContextWithMutableVariables;
+
+use crate::chute::{parachute, pdf};
+
+const USAGE: &str = "Usage:
+    openchute                                   Start the graphical interface
+    openchute export <design.chute> [options]   Export a design without opening a window
+
+Export options:
+    --dxf <path>            Write the pattern to a DXF file
+    --pdf <path>            Write the pattern to a PDF file
+    --paper <size>          PDF paper: full (one page, default), a4 or letter (tiled with alignment marks)
+    --svg <path>            Write the pattern to a true scale SVG file (mm)
+    --fabric-width <width>  Nest all pieces onto a fabric roll of this width, e.g. 1.5 or 60*inch
+    --set <id>=<value>      Override an input value. Value is in SI units and may be an expression, e.g. diameter=8*ft
+";
+
+struct ExportArgs {
+    design: PathBuf,
+    dxf: Option<PathBuf>,
+    pdf: Option<PathBuf>,
+    svg: Option<PathBuf>,
+    paper: pdf::PaperSize,
+    fabric_width: Option<String>,
+    overrides: Vec<(String, String)>,
+}
+
+// Returns true if the arguments should be handled by the command line interface instead of the GUI
+pub fn is_cli_command(args: &[String]) -> bool {
+    matches!(args.first().map(|s| s.as_str()), Some("export" | "help" | "--help" | "-h"))
+}
+
+// Release builds on Windows have no console window. Writes to the console of the shell that started the program instead
+#[cfg(windows)]
+pub fn attach_console() {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    // Fails if already attached (debug builds) or started without a console, output is then dropped as before
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+// Runs a command and returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+    match args.first().map(|s| s.as_str()) {
+        Some("export") => match parse_export_args(&args[1..]) {
+            Ok(export_args) => match export(export_args) {
+                Ok(()) => 0,
+                Err(msg) => {
+                    eprintln!("Error: {}", msg);
+                    1
+                }
+            },
+            Err(msg) => {
+                eprintln!("Error: {}\n\n{}", msg, USAGE);
+                2
+            }
+        },
+        _ => {
+            print!("{}", USAGE);
+            0
+        }
+    }
+}
+
+fn parse_export_args(args: &[String]) -> Result<ExportArgs, String> {
+    let mut design = None;
+    let mut dxf = None;
+    let mut pdf = None;
+    let mut svg = None;
+    let mut paper = pdf::PaperSize::Full;
+    let mut fabric_width = None;
+    let mut overrides = vec![];
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dxf" => dxf = Some(PathBuf::from(iter.next().ok_or("--dxf requires a path")?)),
+            "--pdf" => pdf = Some(PathBuf::from(iter.next().ok_or("--pdf requires a path")?)),
+            "--paper" => {
+                let name = iter.next().ok_or("--paper requires a size")?;
+                paper = pdf::PaperSize::from_name(name).ok_or(format!("Unknown paper size '{}', expected full, a4 or letter", name))?;
+            },
+            "--svg" => svg = Some(PathBuf::from(iter.next().ok_or("--svg requires a path")?)),
+            "--fabric-width" => fabric_width = Some(iter.next().ok_or("--fabric-width requires a width")?.clone()),
+            "--set" => {
+                let assignment = iter.next().ok_or("--set requires <id>=<value>")?;
+                let (id, value) = assignment.split_once('=').ok_or(format!("Invalid assignment '{}', expected <id>=<value>", assignment))?;
+                overrides.push((id.trim().to_owned(), value.trim().to_owned()));
+            },
+            other if other.starts_with("--") => return Err(format!("Unknown option '{}'", other)),
+            other => {
+                if design.is_some() {
+                    return Err(format!("Unexpected argument '{}'", other));
+                }
+                design = Some(PathBuf::from(other));
+            }
+        }
+    }
+
+    let design = design.ok_or("No design file given")?;
+
+    if dxf.is_none() && pdf.is_none() && svg.is_none() {
+        return Err("Nothing to export, give at least one of --dxf, --pdf or --svg".into());
+    }
+
+    Ok(ExportArgs { design, dxf, pdf, svg, paper, fabric_width, overrides })
+}
+
+// Evaluates an expression with the same units as the geometry editor, e.g. "8*ft" or "60*inch"
+fn evaluate(expression: &str) -> Result<f64, evalexpr::EvalexprError> {
+    let mut context = evalexpr::HashMapContext::new();
+    for (name, value) in parachute::ChuteDesigner::default_vars() {
+        context.set_value(name, evalexpr::Value::Float(value))?;
+    }
+    evalexpr::eval_number_with_context(expression, &context)
+}
+
+fn export(args: ExportArgs) -> Result<(), String> {
+    let mut designer = parachute::ChuteDesigner::load_file(&args.design).map_err(|e| format!("Unable to open {}: {}", args.design.display(), e))?;
+
+    for (id, expression) in &args.overrides {
+        let value = evaluate(expression)
+            .map_err(|e| format!("Unable to evaluate '{}' for '{}': {}", expression, id, e))?;
+
+        if !designer.set_input_value(id, value) {
+            return Err(format!("Unknown input '{}'. Available inputs: {}", id, designer.get_input_ids().join(", ")));
+        }
+    }
+
+    if let Some(expression) = &args.fabric_width {
+        let width = evaluate(expression)
+            .map_err(|e| format!("Unable to evaluate fabric width '{}': {}", expression, e))?;
+        designer.set_fabric_width(Some(width));
+    }
+
+    designer.update_calculations();
+
+    if let Some((roll_length, utilization)) = designer.get_nesting_summary().map_err(|e| e.to_string())? {
+        println!("Fabric roll length: {:.2} m, utilization {:.0}%", roll_length, utilization * 100.0);
+    }
+
+    if let Some(path) = args.dxf {
+        designer.export_dxf(path.clone()).map_err(|e| e.to_string())?;
+        println!("Wrote {}", path.display());
+    }
+
+    if let Some(path) = args.pdf {
+        designer.export_pdf(path.clone(), args.paper).map_err(|e| e.to_string())?;
+        println!("Wrote {}", path.display());
+    }
+
+    if let Some(path) = args.svg {
+        designer.export_svg(path.clone()).map_err(|e| e.to_string())?;
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use super::{parse_export_args, evaluate, ExportArgs, USAGE};
+    use crate::chute::pdf::PaperSize;
+
+    fn parse(args: &[&str]) -> Result<ExportArgs, String> {
+        parse_export_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn test_export_args() {
+        let args = parse(&["design.chute", "--pdf", "out.pdf", "--paper", "a4", "--fabric-width", "60*in", "--set", "diameter = 8*ft"]).unwrap();
+        assert_eq!(args.design, PathBuf::from("design.chute"));
+        assert_eq!(args.pdf, Some(PathBuf::from("out.pdf")));
+        assert_eq!(args.paper, PaperSize::A4);
+        assert_eq!(args.fabric_width.as_deref(), Some("60*in"));
+        assert_eq!(args.overrides, vec![("diameter".to_string(), "8*ft".to_string())]);
+    }
+
+    #[test]
+    fn test_missing_values() {
+        assert_eq!(parse(&["design.chute", "--dxf"]).err().unwrap(), "--dxf requires a path");
+        assert_eq!(parse(&["design.chute", "--pdf", "out.pdf", "--paper"]).err().unwrap(), "--paper requires a size");
+        assert_eq!(parse(&["design.chute", "--svg", "out.svg", "--fabric-width"]).err().unwrap(), "--fabric-width requires a width");
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        assert!(parse(&["design.chute", "--svg", "out.svg", "--set", "diameter"]).err().unwrap().starts_with("Invalid assignment 'diameter'"));
+        assert!(parse(&["design.chute", "--pdf", "out.pdf", "--paper", "a3"]).err().unwrap().starts_with("Unknown paper size 'a3'"));
+        assert!(parse(&["design.chute", "--paper", "a4"]).err().unwrap().starts_with("Nothing to export"));
+        assert_eq!(parse(&["--dxf", "out.dxf"]).err().unwrap(), "No design file given");
+    }
+
+    #[test]
+    fn test_usage_examples() {
+        // Every example value in the usage text must evaluate
+        let examples: Vec<&str> = USAGE.lines().filter_map(|line| line.split_once("e.g. ")).flat_map(|(_, examples)| examples.split(" or ")).collect();
+        assert_eq!(examples, vec!["1.5", "60*inch", "diameter=8*ft"]);
+
+        assert_eq!(evaluate("1.5").unwrap(), 1.5);
+        assert!((evaluate("60*inch").unwrap() - 1.524).abs() < 1e-9);
+        let (_, value) = examples[2].split_once('=').unwrap();
+        assert!((evaluate(value).unwrap() - 2.4384).abs() < 1e-9);
+    }
+}
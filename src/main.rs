@@ -6,14 +6,13 @@ use chute::ui;
 use eframe::egui::{self, load::Bytes};
 use eframe::glow;
 mod chute;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
 
 use chute::geometry;
 use chute::parachute;
 use evalexpr::ContextWithMutableVariables;
 
-#[macro_use]
-extern crate uom;
-
 extern crate evalexpr;
 
 // When compiling natively:
@@ -21,9 +20,13 @@ extern crate evalexpr;
 fn main() -> eframe::Result<()> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
-
-    
-
+    // Run headless when called with a command, e.g. `openchute export design.chute --dxf out.dxf`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::is_cli_command(&args) {
+        #[cfg(windows)]
+        cli::attach_console();
+        std::process::exit(cli::run(&args));
+    }
 
     let native_options = eframe::NativeOptions {
         initial_window_size: Some([400.0, 300.0].into()),
@@ -70,6 +73,7 @@ fn main() {
 enum Tab {
     #[default] Design,
     Geometry,
+    Simulation,
     Experiment,
 }
 
@@ -79,6 +83,7 @@ pub struct State {
     use_imperial: bool,
     project_file: Option<PathBuf>,
     display_filename: Option<String>,
+    error_message: Option<String>, // Shown in a dialog until dismissed
 }
 
 
@@ -124,20 +129,32 @@ impl ChuteUI {
             }
             if ui.button("Export DXF").clicked() {
                 if let Some(path) = rfd::FileDialog::new().add_filter("*", &["dxf"]).save_file() {
-                    self.designer.export_dxf(path);   
+                    if let Err(e) = self.designer.export_dxf(path) {
+                        self.state.error_message = Some(e.to_string());
+                    }
                 }
                 ui.close_menu();
             }
-            if ui.button("Export PDF").clicked() {
-                if let Some(path) = rfd::FileDialog::new().add_filter("*", &["pdf"]).save_file() {
-                    self.designer.export_pdf(path);
+            ui.menu_button("Export PDF", |ui| {
+                for paper in [chute::pdf::PaperSize::Full, chute::pdf::PaperSize::A4, chute::pdf::PaperSize::Letter] {
+                    if ui.button(paper.to_string()).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("*", &["pdf"]).save_file() {
+                            if let Err(e) = self.designer.export_pdf(path, paper) {
+                                self.state.error_message = Some(e.to_string());
+                            }
+                        }
+                        ui.close_menu();
+                    }
+                }
+            });
+            if ui.button("Export SVG").clicked() {
+                if let Some(path) = rfd::FileDialog::new().add_filter("*", &["svg"]).save_file() {
+                    if let Err(e) = self.designer.export_svg(path) {
+                        self.state.error_message = Some(e.to_string());
+                    }
                 }
                 ui.close_menu();
             }
-            //if ui.button("Export SVG").clicked() {
-            //    todo!();
-            //    ui.close_menu();
-            //}
         });
 
 
@@ -183,6 +200,10 @@ impl ChuteUI {
                 "Geometry",
                 Tab::Geometry,
             ),
+            (
+                "Simulation",
+                Tab::Simulation,
+            ),
             (
                 "Experiment",
                 Tab::Experiment,
@@ -241,6 +262,7 @@ impl ChuteUI {
             // Only update when underlying data changes
             if self.update_mesh {
                 self.update_mesh = false;
+                self.designer.update_pattern_summary();
                 self.renderer_3d.handle_triangle(ui, Some(self.designer.get_3d_data()));
             } else {
                 self.renderer_3d.handle_triangle(ui, None);
@@ -259,7 +281,13 @@ impl ChuteUI {
         self.designer.geometry_ui(ui, frame, self.state.use_imperial);
     }
 
+    fn simulation_tab(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
+        self.designer.update_calculations();
+        self.designer.simulation_ui(ui, frame, self.state.use_imperial);
+    }
+
     fn experiment_tab(&mut self, ui: &mut egui::Ui, frame: &mut eframe::Frame) {
+        self.designer.update_calculations();
         self.designer.experiment_ui(ui, frame, self.state.use_imperial);
     }
 
@@ -275,24 +303,39 @@ impl ChuteUI {
         // Check validity
         println!("PATH: {:?}", path);
         if path.extension().unwrap_or_default().to_str() == Some("chute") {
-            self.state.display_filename = Some(path.file_name().unwrap().to_str().unwrap().to_owned());
-            self.state.project_file = Some(path.clone());
-            self.designer = parachute::ChuteDesigner::from_json(&std::fs::read_to_string(path).unwrap());
-            self.initialised = true;
-            self.update_mesh = true;
+            // Keep the current design if the file can't be loaded
+            match parachute::ChuteDesigner::load_file(&path) {
+                Ok(designer) => {
+                    self.state.display_filename = Some(path.file_name().unwrap().to_str().unwrap().to_owned());
+                    self.state.project_file = Some(path.clone());
+                    self.designer = designer;
+                    self.initialised = true;
+                    self.update_mesh = true;
+                },
+                Err(e) => {
+                    self.state.error_message = Some(format!("Unable to open {}\n{}", path.display(), e));
+                }
+            }
         }
     }
 
     fn load_project_bytes(&mut self, bytes: Vec<u8>) {
         println!("Loaded bytes: {}", bytes.len());
-        self.state.display_filename = Some(format!("Bytes {}", bytes.len()));
 
-        if let Ok(des) = String::from_utf8(bytes) {
-            self.designer = parachute::ChuteDesigner::from_json(&des);
+        let designer = String::from_utf8(bytes.clone()).map_err(|e| e.to_string())
+            .and_then(|des| parachute::ChuteDesigner::from_json(&des).map_err(|e| e.to_string()));
+
+        match designer {
+            Ok(designer) => {
+                self.state.display_filename = Some(format!("Bytes {}", bytes.len()));
+                self.designer = designer;
+                self.initialised = true;
+                self.update_mesh = true;
+            },
+            Err(e) => {
+                self.state.error_message = Some(format!("Unable to open file\n{}", e));
+            }
         }
-        
-        self.initialised = true;
-        self.update_mesh = true;
     }
 
     fn open_project_file(&mut self) {
@@ -321,10 +364,28 @@ impl ChuteUI {
         
     }
 
+    fn error_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(message) = self.state.error_message.clone() {
+            egui::Window::new("Error")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(message);
+                    if ui.button("OK").clicked() {
+                        self.state.error_message = None;
+                    }
+                });
+        }
+    }
+
     fn save_project_file(&mut self) {
         #[cfg(not(target_arch = "wasm32"))]
         if let Some(path) = rfd::FileDialog::new().add_filter("*", &["chute"]).save_file() {
-            std::fs::write(path.clone(), self.designer.to_json().unwrap());
+            if let Err(e) = self.designer.save_file(&path) {
+                self.state.error_message = Some(format!("Unable to save {}\n{}", path.display(), e));
+                return;
+            }
             self.state.display_filename = Some(path.file_name().unwrap().to_str().unwrap().to_owned());
             self.state.project_file = Some(path);
         }
@@ -367,12 +428,15 @@ impl eframe::App for ChuteUI {
                     match self.state.selected_tab {
                         Tab::Design => self.design_tab(ui, frame),
                         Tab::Geometry => self.geometry_tab(ui, frame),
+                        Tab::Simulation => self.simulation_tab(ui, frame),
                         Tab::Experiment => self.experiment_tab(ui, frame),
                     }
                 }
             })
         });
 
+        self.error_dialog(ctx);
+
         preview_files_being_dropped(ctx);
 
         // Collect dropped files: